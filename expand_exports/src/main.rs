@@ -0,0 +1,164 @@
+//! Prints the `macro_rules!` definition every `#[export_tokens]` item in a source file expands
+//! to, without needing nightly `cargo expand --ugly`:
+//!
+//! ```text
+//! cargo run -p expand_exports -- foreign_crate/src/lib.rs
+//! ```
+//!
+//! The definitions are generated by `macro_magic`'s own `export_tokens_internal`, the function
+//! behind `#[export_tokens]`, so they are exactly what `rustc` would get. Items in inline
+//! modules are included, `mod name;` declarations aren't followed. Items that can't be
+//! expanded are reported after the expansions of all the others.
+//!
+//! `rustc` evaluates the `#[cfg]` and `#[cfg_attr]` attributes of an item before it runs
+//! `#[export_tokens]`, which depends on the features the crate is built with. An item that is
+//! only there under some `#[cfg]` is expanded as if it was enabled, with a note saying under
+//! which condition, and its `#[cfg_attr]`s are left as they are written.
+
+use std::{env, fmt::Write, fs, path::PathBuf, process::ExitCode};
+
+use export_items::{is_export_tokens, item_attrs_mut};
+use macro_magic::mm_core::export_tokens_internal;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use syn::{spanned::Spanned, Attribute, File, Item, Meta};
+
+fn main() -> ExitCode {
+    let Some(path) = env::args_os().nth(1).map(PathBuf::from) else {
+        eprintln!("usage: expand_exports <source file>");
+        return ExitCode::FAILURE;
+    };
+    let src = match fs::read_to_string(&path) {
+        Ok(src) => src,
+        Err(err) => {
+            eprintln!("error: can't read {}: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let file = path.display().to_string();
+    let (expansions, errors) = expand(&file, &src);
+    print!("{expansions}");
+    for error in &errors {
+        eprintln!("error: {error}");
+    }
+    match errors.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+/// Renders the expansion of every `#[export_tokens]` item in `src`, which was read from
+/// `file`, along with the reasons the others can't be expanded.
+fn expand(file: &str, src: &str) -> (String, Vec<String>) {
+    let parsed: File = match syn::parse_str(src) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let error = format!("{file}:{}: {err}", err.span().start().line);
+            return (String::new(), vec![error]);
+        }
+    };
+    let mut out = String::new();
+    let mut errors = Vec::new();
+    let mut pending = parsed.items;
+    pending.reverse();
+    while let Some(mut item) = pending.pop() {
+        if let Item::Mod(module) = &item {
+            if let Some((_, items)) = &module.content {
+                pending.extend(items.iter().rev().cloned());
+            }
+        }
+        let Some(attrs) = item_attrs_mut(&mut item) else {
+            continue;
+        };
+        let Some(position) = attrs.iter().position(is_export_tokens) else {
+            continue;
+        };
+        let attr = attrs.remove(position);
+        let cfgs: Vec<Attribute> = attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .cloned()
+            .collect();
+        attrs.retain(|attr| !attr.path().is_ident("cfg"));
+        let line = attr.span().start().line;
+        let args = match &attr.meta {
+            Meta::List(list) => list.tokens.clone(),
+            _ => TokenStream2::new(),
+        };
+        let definition = match export_tokens_internal(args, item.to_token_stream()) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                errors.push(format!("{file}:{line}: {err}"));
+                continue;
+            }
+        };
+        // the definition is followed by the item itself, which is left out
+        let Ok(File { items, .. }) = syn::parse2::<File>(definition) else {
+            errors.push(format!("{file}:{line}: the expansion doesn't parse"));
+            continue;
+        };
+        let macro_rules = items.into_iter().take(1).collect();
+        writeln!(out, "// {file}:{line}: {}", render(&attr)).unwrap();
+        for cfg in &cfgs {
+            writeln!(out, "// only expanded under {}", render(cfg)).unwrap();
+        }
+        out.push_str(&prettyplease::unparse(&File {
+            shebang: None,
+            attrs: Vec::new(),
+            items: macro_rules,
+        }));
+        out.push('\n');
+    }
+    (out, errors)
+}
+
+/// Renders an attribute the way it would be written by hand.
+fn render(attr: &Attribute) -> String {
+    attr.to_token_stream().to_string().replace(' ', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn foreign_crate_expands() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../foreign_crate/src/lib.rs");
+        let src = fs::read_to_string(path).unwrap();
+        let (expansions, errors) = expand("lib.rs", &src);
+        assert_eq!(errors, Vec::<String>::new());
+        assert!(expansions.contains("macro_rules! __export_tokens_tt_struct_two {"));
+        assert!(expansions.contains("// only expanded under #[cfg(feature=\"extra\")]\n"));
+    }
+
+    #[test]
+    fn readme_example() {
+        let src = "mod first_mod {
+    #[macro_magic::export_tokens]
+    struct MyStruct {
+        field1: usize,
+    }
+}
+";
+        let (expansions, errors) = expand("lib.rs", src);
+        assert_eq!(errors, Vec::<String>::new());
+        let expected = "// lib.rs:2: #[macro_magic::export_tokens]
+#[macro_export]
+macro_rules! __export_tokens_tt_my_struct {";
+        assert!(expansions.starts_with(expected), "{expansions}");
+        assert!(expansions.contains("struct MyStruct { field1 : usize, }"));
+    }
+
+    #[test]
+    fn errors_next_to_expansions() {
+        let src = "#[export_tokens]\nimpl Counter {}\n\n#[export_tokens]\nstruct Counter;\n";
+        let (expansions, errors) = expand("lib.rs", src);
+        assert!(
+            expansions.starts_with("// lib.rs:4: #[export_tokens]\n"),
+            "{expansions}"
+        );
+        assert!(expansions.contains("macro_rules! __export_tokens_tt_counter {"));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("lib.rs:1: "), "{errors:?}");
+    }
+}
@@ -0,0 +1,76 @@
+//! Looks at items the way `#[export_tokens]` sees them, before `rustc` expands or configures
+//! anything. Shared by the crates of this workspace that search parsed source for exported
+//! items.
+
+use syn::{Attribute, Item};
+
+/// Whether `attr` is `#[export_tokens]`, however it is imported.
+pub fn is_export_tokens(attr: &Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "export_tokens")
+}
+
+/// The attributes of `item`, or none if syn doesn't know what kind of item it is.
+pub fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::ExternCrate(item) => &item.attrs,
+        Item::Fn(item) => &item.attrs,
+        Item::ForeignMod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Macro(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Static(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Trait(item) => &item.attrs,
+        Item::TraitAlias(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        Item::Union(item) => &item.attrs,
+        Item::Use(item) => &item.attrs,
+        _ => &[],
+    }
+}
+
+/// Like [`item_attrs`], but mutable, and `None` if syn doesn't know what kind of item it is.
+pub fn item_attrs_mut(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(item) => Some(&mut item.attrs),
+        Item::Enum(item) => Some(&mut item.attrs),
+        Item::ExternCrate(item) => Some(&mut item.attrs),
+        Item::Fn(item) => Some(&mut item.attrs),
+        Item::ForeignMod(item) => Some(&mut item.attrs),
+        Item::Impl(item) => Some(&mut item.attrs),
+        Item::Macro(item) => Some(&mut item.attrs),
+        Item::Mod(item) => Some(&mut item.attrs),
+        Item::Static(item) => Some(&mut item.attrs),
+        Item::Struct(item) => Some(&mut item.attrs),
+        Item::Trait(item) => Some(&mut item.attrs),
+        Item::TraitAlias(item) => Some(&mut item.attrs),
+        Item::Type(item) => Some(&mut item.attrs),
+        Item::Union(item) => Some(&mut item.attrs),
+        Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_tokens_however_imported() {
+        let item: Item = syn::parse_str(
+            "#[macro_magic::export_tokens]
+            #[export_tokens(Name)]
+            #[export]
+            #[derive(Debug)]
+            struct MyStruct;",
+        )
+        .unwrap();
+        let found: Vec<bool> = item_attrs(&item).iter().map(is_export_tokens).collect();
+        assert_eq!(found, [true, true, false, false]);
+    }
+}
@@ -0,0 +1,508 @@
+//! Lists the `#[export_tokens]` items of a crate along with the `__export_tokens_tt_*` macro
+//! each of them defines, and reports the mistakes `rustc` would only point out once the crate
+//! is compiled:
+//!
+//! - items whose export names have the same snake_case form. Their macros are
+//!   `#[macro_export]`ed, so they collide at the crate root even if the items live in
+//!   different modules.
+//! - items without an ident of their own, like impl blocks and `use` items, which need an
+//!   explicit export name.
+//!
+//! Exports that are only there under some `#[cfg]`, their own or that of a module they are in,
+//! are listed with it. Two of them only collide if they can be compiled together, which is
+//! assumed unless one of them requires a predicate the other one requires `not(...)` of.
+//!
+//! ```text
+//! cargo run -p export_names -- foreign_crate
+//! ```
+//!
+//! Takes crate directories or root source files, follows their `mod` declarations and exits
+//! with a non-zero status if it finds a problem.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use export_items::{is_export_tokens, item_attrs};
+use macro_magic::mm_core::export_tokens_macro_ident;
+use quote::ToTokens;
+use syn::{
+    parse::Nothing, punctuated::Punctuated, spanned::Spanned, Attribute, Ident, Item, ItemMod,
+    Meta, Token,
+};
+
+fn main() -> ExitCode {
+    let mut args: Vec<PathBuf> = env::args_os().skip(1).map(PathBuf::from).collect();
+    if args.is_empty() {
+        args.push(PathBuf::from("."));
+    }
+    let mut failed = false;
+    for arg in args {
+        let mut walker = Walker::default();
+        let root = crate_root(&arg);
+        let children = root.parent().unwrap_or(Path::new("")).to_path_buf();
+        walker.file(&root, &children);
+        print!("{}", walker.listing());
+        let problems = walker.problems();
+        for problem in &problems {
+            eprintln!("error: {problem}");
+        }
+        failed |= !problems.is_empty();
+    }
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+/// The root source file of the crate in `dir`, or `path` itself if it is a file.
+fn crate_root(path: &Path) -> PathBuf {
+    if !path.is_dir() {
+        return path.to_path_buf();
+    }
+    let lib = path.join("src").join("lib.rs");
+    match lib.exists() {
+        true => lib,
+        false => path.join("src").join("main.rs"),
+    }
+}
+
+/// An `#[export_tokens]` item.
+struct Export {
+    file: PathBuf,
+    line: usize,
+    /// What the item is, i.e. `struct MyStruct` or `impl`.
+    item: String,
+    /// The `__export_tokens_tt_*` macro the item defines, or why it doesn't have one.
+    macro_name: Result<Ident, String>,
+    /// The predicates of the `#[cfg]`s of the item and the modules it is in.
+    cfgs: Vec<Meta>,
+}
+
+impl Export {
+    fn location(&self) -> String {
+        format!("{}:{}", self.file.display(), self.line)
+    }
+
+    /// The `#[cfg]`s the item is only there under, or nothing if it always is.
+    fn condition(&self) -> String {
+        if self.cfgs.is_empty() {
+            return String::new();
+        }
+        let cfgs: Vec<String> = self
+            .cfgs
+            .iter()
+            .map(|cfg| format!("#[cfg({})]", render(cfg)))
+            .collect();
+        format!(", only under {}", cfgs.join(" "))
+    }
+
+    /// Whether this export and `other` can be compiled together, as far as that can be told
+    /// without knowing the features: they can't if one of them requires `not(p)` of a
+    /// predicate `p` the other one requires.
+    fn compatible(&self, other: &Export) -> bool {
+        let ours = conjuncts(&self.cfgs);
+        let theirs = conjuncts(&other.cfgs);
+        !ours.iter().any(|left| {
+            theirs
+                .iter()
+                .any(|right| negates(left, right) || negates(right, left))
+        })
+    }
+}
+
+/// Walks the module tree of a crate, collecting its `#[export_tokens]` items.
+#[derive(Default)]
+struct Walker {
+    exports: Vec<Export>,
+    errors: Vec<String>,
+    /// The `#[cfg]` predicates of the modules being walked.
+    cfgs: Vec<Meta>,
+}
+
+impl Walker {
+    /// Reads the module at `file`, whose `mod name;` declarations live in `children`.
+    fn file(&mut self, file: &Path, children: &Path) {
+        match fs::read_to_string(file) {
+            Ok(src) => self.source(file, &src, children),
+            Err(err) => self
+                .errors
+                .push(format!("can't read {}: {err}", file.display())),
+        }
+    }
+
+    fn source(&mut self, file: &Path, src: &str, children: &Path) {
+        match syn::parse_file(src) {
+            Ok(parsed) => self.items(file, &parsed.items, children),
+            Err(err) => self.errors.push(format!(
+                "{}:{}: {err}",
+                file.display(),
+                err.span().start().line
+            )),
+        }
+    }
+
+    fn items(&mut self, file: &Path, items: &[Item], children: &Path) {
+        for item in items {
+            let enclosing = self.cfgs.len();
+            self.cfgs
+                .extend(item_attrs(item).iter().filter_map(cfg_predicate));
+            if let Some(attr) = item_attrs(item).iter().find(|attr| is_export_tokens(attr)) {
+                self.exports.push(export(file, item, attr, &self.cfgs));
+            }
+            if let Item::Mod(module) = item {
+                self.module(file, module, children);
+            }
+            self.cfgs.truncate(enclosing);
+        }
+    }
+
+    fn module(&mut self, file: &Path, module: &ItemMod, children: &Path) {
+        match &module.content {
+            Some((_, items)) => self.items(file, items, &children.join(module.ident.to_string())),
+            None => match module_file(module, file, children) {
+                Some((path, dir)) => self.file(&path, &dir),
+                None => self.errors.push(format!(
+                    "{}:{}: can't find the file of `mod {}`",
+                    file.display(),
+                    module.span().start().line,
+                    module.ident
+                )),
+            },
+        }
+    }
+
+    /// Every export, one per line, with the macro it defines.
+    fn listing(&self) -> String {
+        self.exports
+            .iter()
+            .map(|export| {
+                let macro_name = match &export.macro_name {
+                    Ok(ident) => ident.to_string(),
+                    Err(_) => "?".to_string(),
+                };
+                format!(
+                    "{}: {} -> {macro_name}{}\n",
+                    export.location(),
+                    export.item,
+                    export.condition()
+                )
+            })
+            .collect()
+    }
+
+    /// Everything that would keep the crate from compiling, in the order it was found, with
+    /// colliding exports grouped by the macro they define. Exports that can't be compiled
+    /// together don't collide.
+    fn problems(&self) -> Vec<String> {
+        let mut problems = self.errors.clone();
+        let mut by_macro: BTreeMap<String, Vec<&Export>> = BTreeMap::new();
+        for export in &self.exports {
+            match &export.macro_name {
+                Ok(ident) => by_macro.entry(ident.to_string()).or_default().push(export),
+                Err(err) => problems.push(format!("{}: {}", export.location(), err)),
+            }
+        }
+        for (macro_name, exports) in by_macro {
+            let colliding: Vec<&Export> = exports
+                .iter()
+                .enumerate()
+                .filter(|(i, export)| {
+                    exports
+                        .iter()
+                        .enumerate()
+                        .any(|(j, other)| *i != j && export.compatible(other))
+                })
+                .map(|(_, export)| *export)
+                .collect();
+            if colliding.is_empty() {
+                continue;
+            }
+            let mut problem = format!(
+                "`{macro_name}` is defined by {} items, give all but one of them an explicit \
+                 export name:",
+                colliding.len()
+            );
+            for export in colliding {
+                problem.push_str(&format!(
+                    "\n  {}: {}{}",
+                    export.location(),
+                    export.item,
+                    export.condition()
+                ));
+            }
+            problems.push(problem);
+        }
+        problems
+    }
+}
+
+/// Describes an exported item and works out its export name the way `#[export_tokens]` does:
+/// the argument of the attribute if there is one, and the ident of the item otherwise.
+fn export(file: &Path, item: &Item, attr: &Attribute, cfgs: &[Meta]) -> Export {
+    let ident = item_ident(item);
+    let kind = kind(item);
+    let explicit = match &attr.meta {
+        Meta::List(list) if syn::parse2::<Nothing>(list.tokens.clone()).is_err() => Some(
+            syn::parse2::<Ident>(list.tokens.clone())
+                .map_err(|_| "the export name must be a single ident".to_string()),
+        ),
+        _ => None,
+    };
+    let name = match (explicit, &ident) {
+        (Some(name), _) => name,
+        (None, Some(ident)) => Ok(ident.clone()),
+        (None, None) => Err(format!(
+            "this {kind} has no name of its own, give it one with `#[export_tokens(Name)]`"
+        )),
+    };
+    Export {
+        file: file.to_path_buf(),
+        line: attr.span().start().line,
+        item: match ident {
+            Some(ident) => format!("{kind} {ident}"),
+            None => kind.to_string(),
+        },
+        macro_name: name.map(|name| export_tokens_macro_ident(&name)),
+        cfgs: cfgs.to_vec(),
+    }
+}
+
+/// The predicate of `attr` if it is a `#[cfg]`.
+fn cfg_predicate(attr: &Attribute) -> Option<Meta> {
+    match attr.path().is_ident("cfg") {
+        true => attr.parse_args().ok(),
+        false => None,
+    }
+}
+
+/// Splits `all(...)` predicates into the predicates that all have to hold.
+fn conjuncts(cfgs: &[Meta]) -> Vec<Meta> {
+    let mut flat = Vec::new();
+    for cfg in cfgs {
+        let nested = match cfg {
+            Meta::List(list) if list.path.is_ident("all") => list
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok(),
+            _ => None,
+        };
+        match nested {
+            Some(nested) => flat.extend(conjuncts(&nested.into_iter().collect::<Vec<_>>())),
+            None => flat.push(cfg.clone()),
+        }
+    }
+    flat
+}
+
+/// Whether `negation` is `not(predicate)`.
+fn negates(negation: &Meta, predicate: &Meta) -> bool {
+    let Meta::List(list) = negation else {
+        return false;
+    };
+    list.path.is_ident("not")
+        && list
+            .parse_args::<Meta>()
+            .is_ok_and(|inner| render(&inner) == render(predicate))
+}
+
+/// Renders a cfg predicate the way it would be written by hand.
+fn render(tokens: &impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+/// The file `mod name;` in `file` refers to, along with the directory of its own child
+/// modules.
+fn module_file(module: &ItemMod, file: &Path, children: &Path) -> Option<(PathBuf, PathBuf)> {
+    let explicit = module.attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(path),
+                ..
+            }) => Some(path.value()),
+            _ => None,
+        },
+        _ => None,
+    });
+    if let Some(path) = explicit {
+        let path = file.parent()?.join(path);
+        let dir = path.parent()?.to_path_buf();
+        return Some((path, dir));
+    }
+    let name = module.ident.to_string();
+    let dir = children.join(&name);
+    [children.join(format!("{name}.rs")), dir.join("mod.rs")]
+        .into_iter()
+        .find(|path| path.exists())
+        .map(|path| (path, dir))
+}
+
+/// The ident `#[export_tokens]` names an item after when it isn't given one.
+fn item_ident(item: &Item) -> Option<Ident> {
+    match item {
+        Item::Const(item) => Some(item.ident.clone()),
+        Item::Enum(item) => Some(item.ident.clone()),
+        Item::ExternCrate(item) => Some(item.ident.clone()),
+        Item::Fn(item) => Some(item.sig.ident.clone()),
+        Item::Macro(item) => item.ident.clone(),
+        Item::Mod(item) => Some(item.ident.clone()),
+        Item::Static(item) => Some(item.ident.clone()),
+        Item::Struct(item) => Some(item.ident.clone()),
+        Item::Trait(item) => Some(item.ident.clone()),
+        Item::TraitAlias(item) => Some(item.ident.clone()),
+        Item::Type(item) => Some(item.ident.clone()),
+        Item::Union(item) => Some(item.ident.clone()),
+        _ => None,
+    }
+}
+
+fn kind(item: &Item) -> &'static str {
+    match item {
+        Item::Const(_) => "const",
+        Item::Enum(_) => "enum",
+        Item::ExternCrate(_) => "extern crate",
+        Item::Fn(_) => "fn",
+        Item::ForeignMod(_) => "extern block",
+        Item::Impl(_) => "impl",
+        Item::Macro(_) => "macro",
+        Item::Mod(_) => "mod",
+        Item::Static(_) => "static",
+        Item::Struct(_) => "struct",
+        Item::Trait(_) | Item::TraitAlias(_) => "trait",
+        Item::Type(_) => "type",
+        Item::Union(_) => "union",
+        Item::Use(_) => "use",
+        _ => "item",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(src: &str) -> Walker {
+        let mut walker = Walker::default();
+        walker.source(Path::new("src/lib.rs"), src, Path::new("src"));
+        walker
+    }
+
+    #[test]
+    fn foreign_crate_has_no_problems() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../foreign_crate");
+        let mut walker = Walker::default();
+        walker.file(&crate_root(&root), &root.join("src"));
+        assert_eq!(walker.problems(), Vec::<String>::new());
+        let listing = walker.listing();
+        assert!(listing.contains(": struct MyStruct -> __export_tokens_tt_struct_two\n"));
+        assert!(listing.contains(": impl -> __export_tokens_tt_counter_impl\n"));
+        assert!(listing.contains(
+            ": struct ExtraOnly -> __export_tokens_tt_extra_only, only under \
+             #[cfg(feature=\"extra\")]\n"
+        ));
+    }
+
+    #[test]
+    fn collisions_across_modules() {
+        let walker = check(
+            "mod first_mod {
+    #[macro_magic::export_tokens]
+    struct MyStruct {
+        field1: usize,
+    }
+}
+
+mod second_mod {
+    #[macro_magic::export_tokens]
+    struct MyStruct {
+        field1: bool,
+    }
+}
+
+#[export_tokens]
+fn my_struct() {}
+
+#[export_tokens(MyStruct)]
+struct Renamed;
+",
+        );
+        assert_eq!(
+            walker.problems(),
+            [
+                "`__export_tokens_tt_my_struct` is defined by 4 items, give all but one of them \
+                 an explicit export name:
+  src/lib.rs:2: struct MyStruct
+  src/lib.rs:9: struct MyStruct
+  src/lib.rs:15: fn my_struct
+  src/lib.rs:18: struct Renamed"
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusive_cfgs_dont_collide() {
+        let walker = check(
+            r#"#[cfg(feature = "a")]
+#[export_tokens]
+struct Backend;
+
+#[cfg(not(feature = "a"))]
+#[export_tokens]
+struct Backend;
+
+#[cfg(all(unix, not(feature = "a")))]
+mod unix {
+    #[export_tokens]
+    fn backend() {}
+}
+"#,
+        );
+        assert_eq!(
+            walker.listing(),
+            "src/lib.rs:2: struct Backend -> __export_tokens_tt_backend, only under \
+             #[cfg(feature=\"a\")]\n\
+             src/lib.rs:6: struct Backend -> __export_tokens_tt_backend, only under \
+             #[cfg(not(feature=\"a\"))]\n\
+             src/lib.rs:11: fn backend -> __export_tokens_tt_backend, only under \
+             #[cfg(all(unix,not(feature=\"a\")))]\n"
+        );
+        assert_eq!(
+            walker.problems(),
+            [
+                "`__export_tokens_tt_backend` is defined by 2 items, give all but one of them \
+                 an explicit export name:
+  src/lib.rs:6: struct Backend, only under #[cfg(not(feature=\"a\"))]
+  src/lib.rs:11: fn backend, only under #[cfg(all(unix,not(feature=\"a\")))]"
+            ]
+        );
+    }
+
+    #[test]
+    fn items_without_a_name() {
+        let walker = check(
+            "#[export_tokens]
+impl Counter {}
+
+#[export_tokens(CounterImpl)]
+impl Counter {}
+
+#[export_tokens(a::b)]
+use std::fmt;
+",
+        );
+        assert_eq!(
+            walker.listing(),
+            "src/lib.rs:1: impl -> ?\nsrc/lib.rs:4: impl -> __export_tokens_tt_counter_impl\n\
+             src/lib.rs:7: use -> ?\n"
+        );
+        assert_eq!(
+            walker.problems(),
+            [
+                "src/lib.rs:1: this impl has no name of its own, give it one with \
+                 `#[export_tokens(Name)]`",
+                "src/lib.rs:7: the export name must be a single ident"
+            ]
+        );
+    }
+}
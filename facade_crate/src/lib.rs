@@ -0,0 +1,55 @@
+//! The one crate users of our macros depend on. It re-exports the macros of `macros_crate`,
+//! along with the `macro_magic` and `types_crate` paths their expansions refer to.
+//!
+//! Each macro is re-exported from the hidden `__facade_*` entry point `macros_crate` has for
+//! it, which refers to those paths through this crate rather than as `::macro_magic` and
+//! `::types_crate`. Crates that use `macros_crate` directly keep getting the plain paths,
+//! whether or not this crate is built alongside them.
+//!
+//! ```ignore
+//! #[facade_crate::use_proc]
+//! use facade_crate::make_item_const;
+//!
+//! make_item_const!(foreign_crate::StructTwo);
+//! ```
+
+pub use macro_magic::{export_tokens, use_attr, use_proc};
+pub use macros_crate::{
+    __facade_assert_item_fingerprint as assert_item_fingerprint,
+    __facade_assert_items_equivalent as assert_items_equivalent,
+    __facade_assert_same_shape as assert_same_shape, __facade_copy_fields_from as copy_fields_from,
+    __facade_delegate_trait as delegate_trait, __facade_extend_enum as extend_enum,
+    __facade_field_names as field_names, __facade_field_types as field_types,
+    __facade_inline_foreign_fn as inline_foreign_fn, __facade_item_docs as item_docs,
+    __facade_item_fingerprint as item_fingerprint, __facade_item_meta as item_meta,
+    __facade_json_schema as json_schema, __facade_list_module_items as list_module_items,
+    __facade_make_item_const as make_item_const, __facade_make_item_source as make_item_source,
+    __facade_make_items_const as make_items_const, __facade_mirror_item as mirror_item,
+    __facade_mock_of as mock_of, __facade_module_item_src as module_item_src,
+    __facade_print_foreign_item as print_foreign_item,
+};
+
+// The inner macros `#[use_proc]` and `#[use_attr]` import along with the macros above. Those
+// that refer to `macro_magic` or `types_crate` themselves have a `__facade_*` entry point too.
+#[doc(hidden)]
+pub use macros_crate::{
+    __facade_import_tokens_attr_mock_of_inner as __import_tokens_attr_mock_of_inner,
+    __facade_import_tokens_proc_assert_items_equivalent_inner as __import_tokens_proc_assert_items_equivalent_inner,
+    __facade_import_tokens_proc_item_docs_inner as __import_tokens_proc_item_docs_inner,
+    __facade_import_tokens_proc_item_meta_inner as __import_tokens_proc_item_meta_inner,
+    __facade_import_tokens_proc_list_module_items_inner as __import_tokens_proc_list_module_items_inner,
+    __facade_import_tokens_proc_make_items_const_inner as __import_tokens_proc_make_items_const_inner,
+    __import_tokens_attr_assert_same_shape_inner, __import_tokens_attr_copy_fields_from_inner,
+    __import_tokens_attr_delegate_trait_inner, __import_tokens_attr_extend_enum_inner,
+    __import_tokens_proc_assert_item_fingerprint_inner, __import_tokens_proc_field_names_inner,
+    __import_tokens_proc_field_types_inner, __import_tokens_proc_inline_foreign_fn_inner,
+    __import_tokens_proc_item_fingerprint_inner, __import_tokens_proc_json_schema_inner,
+    __import_tokens_proc_make_item_const_inner, __import_tokens_proc_make_item_source_inner,
+    __import_tokens_proc_mirror_item_inner, __import_tokens_proc_module_item_src_inner,
+    __import_tokens_proc_print_foreign_item_inner,
+};
+
+#[doc(hidden)]
+pub use macro_magic;
+#[doc(hidden)]
+pub use types_crate;
@@ -1,8 +1,15 @@
+#[macro_magic::export_tokens(FirstMod)]
 mod first_mod {
     #[macro_magic::export_tokens(StructOne)]
     struct MyStruct {
         field1: usize,
     }
+
+    #[macro_magic::export_tokens(PointOne)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
 }
 
 mod second_mod {
@@ -10,4 +17,175 @@ mod second_mod {
     struct MyStruct {
         field1: bool,
     }
+
+    #[macro_magic::export_tokens(PointTwo)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+}
+
+pub use third_mod::{Config, Describe, ErrorKind, Reply, Storage};
+
+// `classify` uses `crate::` paths, which end up in the `macro_rules!` its export generates
+#[allow(clippy::crate_in_macro_def)]
+mod third_mod {
+    /// A shape with dimensions of type `T`.
+    ///
+    /// Covers generics and every style of variant.
+    #[macro_magic::export_tokens]
+    #[derive(Copy, Clone, Debug)]
+    pub enum Shape<T: Copy> {
+        /// A circle, described by its radius.
+        Circle {
+            /// The distance from the center to the edge.
+            radius: T,
+        },
+        /// A rectangle, described by its width and height.
+        Rect(T, T),
+        Empty,
+    }
+
+    #[macro_magic::export_tokens]
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum ErrorKind {
+        NotFound,
+        PermissionDenied { path: String },
+        Other(u16),
+    }
+
+    /// Either a value or the reason there is none.
+    #[macro_magic::export_tokens]
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Reply<T> {
+        Value(T),
+        Busy,
+    }
+
+    #[macro_magic::export_tokens]
+    pub trait Describe {
+        const NAME: &'static str;
+        type Output;
+        fn describe(&self, verbose: bool) -> Self::Output;
+    }
+
+    #[macro_magic::export_tokens]
+    pub trait Storage {
+        fn get(&self, key: &str) -> Option<&str>;
+        fn set(&mut self, key: String, value: String);
+        fn clear(&mut self);
+    }
+
+    /// A byte with an optional label.
+    #[macro_magic::export_tokens]
+    pub struct Pair(
+        /// The byte.
+        pub u8,
+        pub Option<String>,
+    );
+
+    #[macro_magic::export_tokens]
+    pub struct Marker;
+
+    /// Settings whose fields depend on the `extra` feature.
+    #[macro_magic::export_tokens]
+    #[cfg_attr(feature = "extra", derive(Debug))]
+    pub struct Config {
+        pub name: String,
+        #[cfg(feature = "extra")]
+        pub verbose: bool,
+        #[cfg(not(feature = "extra"))]
+        pub quiet: bool,
+    }
+
+    /// Only exported with the `extra` feature. `rustc` evaluates an item's own `#[cfg]` before
+    /// expanding any attribute macros on it, so without the feature there is no
+    /// `__export_tokens_tt_*` macro either.
+    #[cfg(feature = "extra")]
+    #[macro_magic::export_tokens]
+    pub struct ExtraOnly {
+        pub level: u8,
+    }
+
+    /// Maps an HTTP-like status code to an error.
+    #[macro_magic::export_tokens]
+    fn classify(code: u16) -> Option<crate::ErrorKind> {
+        use crate::ErrorKind::*;
+        match code {
+            200..=299 => None,
+            404 => Some(NotFound),
+            403 => Some(crate::ErrorKind::PermissionDenied {
+                path: String::new(),
+            }),
+            code => Some(crate::ErrorKind::Other(code)),
+        }
+    }
+
+    /// The number of decimal digits of `n`.
+    #[macro_magic::export_tokens]
+    fn digits(n: u64) -> u32 {
+        1 + (n >= 10).then_some(n / 10).map_or(0, digits)
+    }
+
+    /// Refers to `classify` relative to this module, which `inline_foreign_fn!` can't follow.
+    #[macro_magic::export_tokens]
+    fn is_error(code: u16) -> bool {
+        self::classify(code).is_some()
+    }
+
+    #[macro_magic::export_tokens]
+    pub(crate) fn area(width: usize, height: usize) -> usize {
+        width * height
+    }
+}
+
+/// One fixture for every kind of item `#[export_tokens]` accepts, including the module itself.
+/// Items without an ident of their own, like impl blocks and `use` items, need an explicit
+/// export name.
+#[macro_magic::export_tokens(FourthMod)]
+mod fourth_mod {
+    use super::Describe;
+
+    #[macro_magic::export_tokens]
+    pub const MAX_RETRIES: u32 = 3;
+
+    #[macro_magic::export_tokens]
+    pub static GREETING: &str = "hello";
+
+    #[macro_magic::export_tokens]
+    pub type Grid = Vec<Vec<u8>>;
+
+    #[macro_magic::export_tokens]
+    #[repr(C)]
+    pub union IntOrFloat {
+        pub int: u32,
+        pub float: f32,
+    }
+
+    #[macro_magic::export_tokens]
+    pub struct Counter(u32);
+
+    #[macro_magic::export_tokens(CounterImpl)]
+    impl Counter {
+        pub fn increment(&mut self) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    #[macro_magic::export_tokens(DescribeCounter)]
+    impl Describe for Counter {
+        const NAME: &'static str = "counter";
+        type Output = u32;
+
+        fn describe(&self, _verbose: bool) -> u32 {
+            self.0
+        }
+    }
+
+    #[macro_magic::export_tokens(HashMapUse)]
+    use std::collections::HashMap;
+
+    #[macro_magic::export_tokens(FmtUse)]
+    use std::fmt::{self, Display};
 }
@@ -0,0 +1,116 @@
+//! The JSON lines catalog `print_foreign_item!` writes imported items to.
+
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use crate::json::Json;
+
+/// Environment variable that overrides where the catalog is written.
+const CATALOG_ENV_VAR: &str = "FOREIGN_ITEM_CATALOG";
+
+/// File name of the catalog inside the target directory.
+const CATALOG_FILE_NAME: &str = "foreign_item_catalog.jsonl";
+
+/// A single line of the catalog.
+pub struct CatalogEntry<'a> {
+    /// Name of the crate that imported the item.
+    pub krate: &'a str,
+    /// Export name of the item, i.e. the last segment of `path`.
+    pub export_name: &'a str,
+    /// Path the item was imported from, made absolute with [`resolve_path`].
+    pub path: &'a str,
+    /// Tokens of the item.
+    pub tokens: &'a str,
+}
+
+impl CatalogEntry<'_> {
+    pub fn to_json(&self) -> String {
+        Json::object([
+            ("crate", Json::string(self.krate)),
+            ("export_name", Json::string(self.export_name)),
+            ("path", Json::string(self.path)),
+            ("tokens", Json::string(self.tokens)),
+        ])
+        .to_string()
+    }
+}
+
+/// Resolves the catalog path: `$FOREIGN_ITEM_CATALOG` if set, otherwise
+/// `foreign_item_catalog.jsonl` in `$CARGO_TARGET_DIR`, falling back to the `target`
+/// directory of the crate being compiled.
+pub fn catalog_path() -> PathBuf {
+    if let Some(path) = env::var_os(CATALOG_ENV_VAR) {
+        return PathBuf::from(path);
+    }
+    let target_dir = match env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default()).join("target"),
+    };
+    target_dir.join(CATALOG_FILE_NAME)
+}
+
+/// Makes a path as written at the call site absolute: a leading `::` is dropped and `crate`
+/// becomes the name of `krate`, the calling crate. `self` and `super` depend on the module of
+/// the call site, which a proc macro can't see, so those paths are kept as they are.
+pub fn resolve_path(path: &str, krate: &str) -> String {
+    let path = path.strip_prefix("::").unwrap_or(path);
+    match path.strip_prefix("crate::") {
+        Some(rest) => format!("{}::{rest}", krate.replace('-', "_")),
+        None => path.to_string(),
+    }
+}
+
+/// Appends `entry` to the catalog, unless an identical entry is already there from a previous
+/// build. Returns the path of the catalog.
+///
+/// Crates that are compiled in parallel record their entries at the same time, so the catalog
+/// is read and appended to through a single handle that is locked in between, and every entry
+/// is written with a single `write_all`. `File::lock` is why `macros_crate` needs Rust 1.89.
+pub fn record(entry: &CatalogEntry) -> io::Result<PathBuf> {
+    let path = catalog_path();
+    let line = format!("{}\n", entry.to_json());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(&path)?;
+    file.lock()?;
+    let mut existing = String::new();
+    file.read_to_string(&mut existing)?;
+    if !existing
+        .lines()
+        .any(|existing_line| existing_line == line.trim_end())
+    {
+        file.write_all(line.as_bytes())?;
+    }
+    file.unlock()?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_path;
+
+    #[test]
+    fn resolves_paths() {
+        assert_eq!(
+            resolve_path("foreign_crate::StructOne", "user_crate"),
+            "foreign_crate::StructOne"
+        );
+        assert_eq!(
+            resolve_path("::foreign_crate::StructOne", "user_crate"),
+            "foreign_crate::StructOne"
+        );
+        assert_eq!(
+            resolve_path("crate::Local", "user-crate"),
+            "user_crate::Local"
+        );
+        assert_eq!(resolve_path("self::Local", "user_crate"), "self::Local");
+    }
+}
@@ -0,0 +1,258 @@
+//! Evaluates `#[cfg]` and `#[cfg_attr]` attributes of imported items with the features of the
+//! calling crate.
+//!
+//! `#[export_tokens]` sees items before `rustc` strips anything configured out of them, so
+//! their tokens still carry the `#[cfg]` attributes of their fields, variants and so on. A
+//! proc macro can't evaluate those itself, as it doesn't know which features are enabled, but
+//! it can let `rustc` do it: the macro's output is expanded once for every combination of the
+//! predicates the item uses, each gated on `#[cfg(all(...))]` matching that combination, so
+//! exactly one of them survives.
+
+use export_items::item_attrs_mut;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::Parser,
+    punctuated::Punctuated,
+    visit::Visit,
+    visit_mut::{self, VisitMut},
+    Attribute, Block, Error, ExprMatch, FieldsNamed, FieldsUnnamed, ImplItem, Item, ItemEnum,
+    ItemImpl, ItemMod, ItemTrait, Meta, Result, Stmt, Token, TraitItem,
+};
+
+/// Every combination is emitted, so this keeps the expansion to a reasonable size.
+const MAX_PREDICATES: usize = 6;
+
+/// Calls `emit` with a copy of `item` configured for every combination of the cfg predicates
+/// it uses, and gates each of the results, which must be a single item, on that combination.
+pub fn per_cfg(item: &Item, emit: impl Fn(&Item) -> TokenStream2) -> Result<TokenStream2> {
+    let mut collect = Collect::default();
+    collect.visit_item(item);
+    let predicates = collect.predicates;
+    if predicates.is_empty() {
+        return Ok(emit(item));
+    }
+    if predicates.len() > MAX_PREDICATES {
+        return Err(Error::new(
+            Span::call_site(),
+            format!(
+                "the item uses {} distinct cfg predicates, at most {MAX_PREDICATES} can be \
+                 evaluated",
+                predicates.len()
+            ),
+        ));
+    }
+    let mut output = TokenStream2::new();
+    for combination in 0..1u32 << predicates.len() {
+        let values: Vec<bool> = (0..predicates.len())
+            .map(|i| combination & (1 << i) != 0)
+            .collect();
+        let mut apply = Apply {
+            predicates: &predicates,
+            values: &values,
+        };
+        let mut configured = item.clone();
+        if let Some(attrs) = item_attrs_mut(&mut configured) {
+            if !apply.configure(attrs) {
+                continue;
+            }
+        }
+        apply.visit_item_mut(&mut configured);
+        let gates = predicates
+            .iter()
+            .zip(&values)
+            .map(|(predicate, value)| match value {
+                true => quote!(#predicate),
+                false => quote!(not(#predicate)),
+            });
+        let emitted = emit(&configured);
+        output.extend(quote! {
+            #[cfg(all(#(#gates),*))]
+            #emitted
+        });
+    }
+    Ok(output)
+}
+
+/// The predicate of a `#[cfg(..)]` attribute.
+fn cfg_predicate(attr: &Attribute) -> Option<Meta> {
+    match attr.path().is_ident("cfg") {
+        true => attr.parse_args().ok(),
+        false => None,
+    }
+}
+
+/// The predicate and attributes of a `#[cfg_attr(.., ..)]` attribute.
+fn cfg_attr_parts(attr: &Attribute) -> Option<(Meta, Vec<Meta>)> {
+    if !attr.path().is_ident("cfg_attr") {
+        return None;
+    }
+    let Meta::List(list) = &attr.meta else {
+        return None;
+    };
+    let parser = |input: syn::parse::ParseStream| {
+        let predicate = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let attrs = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        Ok((predicate, attrs.into_iter().collect()))
+    };
+    parser.parse2(list.tokens.clone()).ok()
+}
+
+/// Collects the distinct predicates of every `#[cfg]` and `#[cfg_attr]` in an item.
+#[derive(Default)]
+struct Collect {
+    predicates: Vec<Meta>,
+}
+
+impl Collect {
+    /// Adds the predicates of `attr`, including those of `#[cfg_attr]`s nested in it.
+    fn collect(&mut self, attr: &Attribute) {
+        let mut found = Vec::new();
+        if let Some(predicate) = cfg_predicate(attr) {
+            found.push(predicate);
+        }
+        if let Some((predicate, attrs)) = cfg_attr_parts(attr) {
+            found.push(predicate);
+            for meta in attrs {
+                let attr: Attribute = syn::parse_quote!(#[#meta]);
+                self.collect(&attr);
+            }
+        }
+        for predicate in found {
+            let key = predicate.to_token_stream().to_string();
+            if !self
+                .predicates
+                .iter()
+                .any(|p| p.to_token_stream().to_string() == key)
+            {
+                self.predicates.push(predicate);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Collect {
+    fn visit_attribute(&mut self, attr: &'ast Attribute) {
+        self.collect(attr);
+    }
+}
+
+/// Strips everything that is configured out under one combination of predicate values.
+struct Apply<'a> {
+    predicates: &'a [Meta],
+    values: &'a [bool],
+}
+
+impl Apply<'_> {
+    fn evaluate(&self, predicate: &Meta) -> bool {
+        let key = predicate.to_token_stream().to_string();
+        self.predicates
+            .iter()
+            .position(|p| p.to_token_stream().to_string() == key)
+            .map(|i| self.values[i])
+            .unwrap_or(true)
+    }
+
+    /// Expands the `#[cfg_attr]`s in `attrs` and removes its `#[cfg]`s, returning whether the
+    /// element they are attached to is configured in.
+    fn configure(&self, attrs: &mut Vec<Attribute>) -> bool {
+        let mut enabled = true;
+        let mut configured = Vec::new();
+        let mut pending: Vec<Attribute> = std::mem::take(attrs);
+        pending.reverse();
+        while let Some(attr) = pending.pop() {
+            if let Some(predicate) = cfg_predicate(&attr) {
+                enabled &= self.evaluate(&predicate);
+            } else if let Some((predicate, metas)) = cfg_attr_parts(&attr) {
+                if self.evaluate(&predicate) {
+                    for meta in metas.into_iter().rev() {
+                        pending.push(syn::parse_quote!(#[#meta]));
+                    }
+                }
+            } else {
+                configured.push(attr);
+            }
+        }
+        *attrs = configured;
+        enabled
+    }
+
+    fn retain<T>(&self, items: &mut Vec<T>, attrs: impl Fn(&mut T) -> Option<&mut Vec<Attribute>>) {
+        items.retain_mut(|item| match attrs(item) {
+            Some(attrs) => self.configure(attrs),
+            None => true,
+        });
+    }
+
+    fn retain_punctuated<T, P: Default>(
+        &self,
+        items: &mut Punctuated<T, P>,
+        attrs: impl Fn(&mut T) -> &mut Vec<Attribute>,
+    ) {
+        let mut kept: Vec<T> = std::mem::take(items).into_iter().collect();
+        kept.retain_mut(|item| self.configure(attrs(item)));
+        items.extend(kept);
+    }
+}
+
+impl VisitMut for Apply<'_> {
+    fn visit_fields_named_mut(&mut self, fields: &mut FieldsNamed) {
+        self.retain_punctuated(&mut fields.named, |field| &mut field.attrs);
+        visit_mut::visit_fields_named_mut(self, fields);
+    }
+
+    fn visit_fields_unnamed_mut(&mut self, fields: &mut FieldsUnnamed) {
+        self.retain_punctuated(&mut fields.unnamed, |field| &mut field.attrs);
+        visit_mut::visit_fields_unnamed_mut(self, fields);
+    }
+
+    fn visit_item_enum_mut(&mut self, item: &mut ItemEnum) {
+        self.retain_punctuated(&mut item.variants, |variant| &mut variant.attrs);
+        visit_mut::visit_item_enum_mut(self, item);
+    }
+
+    fn visit_item_mod_mut(&mut self, item: &mut ItemMod) {
+        if let Some((_, items)) = &mut item.content {
+            self.retain(items, item_attrs_mut);
+        }
+        visit_mut::visit_item_mod_mut(self, item);
+    }
+
+    fn visit_item_trait_mut(&mut self, item: &mut ItemTrait) {
+        self.retain(&mut item.items, |item| match item {
+            TraitItem::Const(item) => Some(&mut item.attrs),
+            TraitItem::Fn(item) => Some(&mut item.attrs),
+            TraitItem::Type(item) => Some(&mut item.attrs),
+            TraitItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        });
+        visit_mut::visit_item_trait_mut(self, item);
+    }
+
+    fn visit_item_impl_mut(&mut self, item: &mut ItemImpl) {
+        self.retain(&mut item.items, |item| match item {
+            ImplItem::Const(item) => Some(&mut item.attrs),
+            ImplItem::Fn(item) => Some(&mut item.attrs),
+            ImplItem::Type(item) => Some(&mut item.attrs),
+            ImplItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        });
+        visit_mut::visit_item_impl_mut(self, item);
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        self.retain(&mut block.stmts, |stmt| match stmt {
+            Stmt::Local(local) => Some(&mut local.attrs),
+            Stmt::Item(item) => item_attrs_mut(item),
+            Stmt::Macro(stmt) => Some(&mut stmt.attrs),
+            Stmt::Expr(..) => None,
+        });
+        visit_mut::visit_block_mut(self, block);
+    }
+
+    fn visit_expr_match_mut(&mut self, expr: &mut ExprMatch) {
+        self.retain(&mut expr.arms, |arm| Some(&mut arm.attrs));
+        visit_mut::visit_expr_match_mut(self, expr);
+    }
+}
@@ -0,0 +1,107 @@
+//! Imports several `#[export_tokens]` items in a single macro invocation by chaining
+//! `forward_tokens!` calls.
+//!
+//! Each call imports one item and hands it to the same hidden inner macro, which adds it to
+//! the items collected so far and then either forwards the next path back to itself or, once
+//! every path has been resolved, gets to see all of the items at once. The state travels in
+//! the `$extra` block of `forward_tokens!`, which the `__export_tokens_tt_*` macros match as an
+//! `expr`, so it is encoded as a block of valid statements:
+//!
+//! ```ignore
+//! {
+//!     mod __collected { struct MyStruct { field1: usize, } }
+//!     [foreign_crate::StructTwo];
+//!     payload
+//! }
+//! ```
+//!
+//! where `payload` is an arbitrary expression the calling macro uses for its own arguments.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, Item, Path, Result, Token,
+};
+
+use crate::Roots;
+
+/// A chain of imports that is still in progress.
+pub struct Chain {
+    /// Items that have been imported so far, in the order their paths were given.
+    pub collected: Vec<Item>,
+    /// Paths that have yet to be imported.
+    pub remaining: Vec<Path>,
+    /// Arguments of the calling macro that are carried along unchanged.
+    pub payload: Expr,
+}
+
+impl Chain {
+    /// Starts a chain that imports `paths`, which must not be empty.
+    pub fn new(paths: Vec<Path>, payload: Expr) -> Self {
+        Chain {
+            collected: Vec::new(),
+            remaining: paths,
+            payload,
+        }
+    }
+
+    /// Expands to a `forward_tokens!` call that imports the next remaining path and hands it,
+    /// along with the rest of the chain, to `inner_macro`.
+    pub fn forward(mut self, inner_macro: &str, roots: &Roots) -> TokenStream2 {
+        let next = self.remaining.remove(0);
+        let inner_macro = Ident::new(inner_macro, Span::call_site());
+        let mm_path = &roots.macro_magic;
+        let Chain {
+            collected,
+            remaining,
+            payload,
+        } = self;
+        quote! {
+            #mm_path::forward_tokens! {
+                #next,
+                #inner_macro,
+                #mm_path,
+                {
+                    mod __collected { #(#collected)* }
+                    [#(#remaining),*];
+                    #payload
+                }
+            }
+        }
+    }
+}
+
+/// What the hidden inner macro of a chain receives: the item that was just imported followed
+/// by the rest of the chain.
+pub struct ForwardedChain(pub Chain);
+
+impl Parse for ForwardedChain {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        extra.parse::<Token![mod]>()?;
+        extra.parse::<Ident>()?;
+        let collected_tokens;
+        braced!(collected_tokens in extra);
+        let mut collected = Vec::new();
+        while !collected_tokens.is_empty() {
+            collected.push(collected_tokens.parse()?);
+        }
+        collected.push(item);
+        let remaining_tokens;
+        bracketed!(remaining_tokens in extra);
+        let remaining = Punctuated::<Path, Token![,]>::parse_terminated(&remaining_tokens)?;
+        extra.parse::<Token![;]>()?;
+        let payload = extra.parse()?;
+        Ok(ForwardedChain(Chain {
+            collected,
+            remaining: remaining.into_iter().collect(),
+            payload,
+        }))
+    }
+}
@@ -0,0 +1,240 @@
+//! Structural comparison of items, used by `assert_items_equivalent!` and `#[assert_same_shape]`.
+
+use syn::{spanned::Spanned, Error, Field, Fields, Item, ItemStruct};
+
+use crate::pretty;
+
+/// Compares two structs or enums field by field (and variant by variant), returning every
+/// difference between them, written as `left vs right`. Attributes, visibility and the names
+/// of the items themselves are ignored.
+pub fn compare_items(left: &Item, right: &Item) -> Vec<String> {
+    match (left, right) {
+        (Item::Struct(left), Item::Struct(right)) => {
+            compare_fields(&left.fields, &right.fields, "")
+        }
+        (Item::Enum(left), Item::Enum(right)) => {
+            let mut differences = Vec::new();
+            for left_variant in &left.variants {
+                let name = left_variant.ident.to_string();
+                match right
+                    .variants
+                    .iter()
+                    .find(|v| v.ident == left_variant.ident)
+                {
+                    Some(right_variant) => differences.extend(compare_fields(
+                        &left_variant.fields,
+                        &right_variant.fields,
+                        &format!("{name}::"),
+                    )),
+                    None => differences.push(format!("variant {name}: present vs (missing)")),
+                }
+            }
+            for right_variant in &right.variants {
+                if !left.variants.iter().any(|v| v.ident == right_variant.ident) {
+                    differences.push(format!(
+                        "variant {}: (missing) vs present",
+                        right_variant.ident
+                    ));
+                }
+            }
+            let left_order = left.variants.iter().map(|v| v.ident.to_string());
+            let right_order = right.variants.iter().map(|v| v.ident.to_string());
+            differences.extend(compare_order("variant order", left_order, right_order));
+            differences
+        }
+        (left, right) if kind(left) == kind(right) => {
+            vec![format!("kind: {} items can't be compared", kind(left))]
+        }
+        (left, right) => vec![format!("kind: {} vs {}", kind(left), kind(right))],
+    }
+}
+
+/// Compares two sets of fields by name (or by index, for tuple fields), prefixing every field
+/// name with `prefix` in the messages.
+pub fn compare_fields(left: &Fields, right: &Fields, prefix: &str) -> Vec<String> {
+    let left_style = fields_style(left);
+    let right_style = fields_style(right);
+    if left_style != right_style {
+        return vec![format!("{prefix}fields: {left_style} vs {right_style}")];
+    }
+    let mut differences = Vec::new();
+    for (i, left_field) in left.iter().enumerate() {
+        let name = field_name(left_field, i);
+        let left_ty = pretty::ty(&left_field.ty);
+        let right_ty = right
+            .iter()
+            .enumerate()
+            .find(|(j, right_field)| field_name(right_field, *j) == name)
+            .map(|(_, right_field)| pretty::ty(&right_field.ty));
+        match right_ty {
+            Some(right_ty) if right_ty == left_ty => {}
+            Some(right_ty) => differences.push(format!("{prefix}{name}: {left_ty} vs {right_ty}")),
+            None => differences.push(format!("{prefix}{name}: {left_ty} vs (missing)")),
+        }
+    }
+    for (j, right_field) in right.iter().enumerate() {
+        let name = field_name(right_field, j);
+        if !left
+            .iter()
+            .enumerate()
+            .any(|(i, f)| field_name(f, i) == name)
+        {
+            differences.push(format!(
+                "{prefix}{name}: (missing) vs {}",
+                pretty::ty(&right_field.ty)
+            ));
+        }
+    }
+    if let Fields::Named(_) = left {
+        let left_order = left.iter().enumerate().map(|(i, f)| field_name(f, i));
+        let right_order = right.iter().enumerate().map(|(j, f)| field_name(f, j));
+        differences.extend(compare_order(
+            &format!("{prefix}field order"),
+            left_order,
+            right_order,
+        ));
+    }
+    differences
+}
+
+/// Checks that `local` declares the same fields as the imported struct, in the same order and
+/// with the same types, returning an error spanned on the offending local field for every
+/// difference. Fields the local struct lacks are reported on its name.
+pub fn shape_errors(local: &ItemStruct, imported: &Item) -> Vec<Error> {
+    let Item::Struct(imported) = imported else {
+        return vec![Error::new(
+            local.ident.span(),
+            format!(
+                "expected a struct to compare with, not a {}",
+                kind(imported)
+            ),
+        )];
+    };
+    let local_style = fields_style(&local.fields);
+    let imported_style = fields_style(&imported.fields);
+    if local_style != imported_style {
+        return vec![Error::new(
+            local.ident.span(),
+            format!(
+                "`{}` has {local_style} fields, but the imported `{}` has {imported_style} fields",
+                local.ident, imported.ident
+            ),
+        )];
+    }
+    let imported_fields: Vec<(String, String)> = imported
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| (field_name(field, i), pretty::ty(&field.ty)))
+        .collect();
+    let mut errors = Vec::new();
+    for (i, field) in local.fields.iter().enumerate() {
+        let name = field_name(field, i);
+        let ty = pretty::ty(&field.ty);
+        let span = match &field.ident {
+            Some(ident) => ident.span(),
+            None => field.ty.span(),
+        };
+        match imported_fields.iter().position(|(other, _)| *other == name) {
+            None => errors.push(Error::new(
+                span,
+                format!("`{}` has no field `{name}`", imported.ident),
+            )),
+            Some(j) => {
+                if j != i {
+                    errors.push(Error::new(
+                        span,
+                        format!(
+                            "`{name}` is field {} of `{}`, but field {} here",
+                            j + 1,
+                            imported.ident,
+                            i + 1
+                        ),
+                    ));
+                }
+                if imported_fields[j].1 != ty {
+                    errors.push(Error::new(
+                        field.ty.span(),
+                        format!(
+                            "`{name}` is a `{}` in `{}`, not a `{ty}`",
+                            imported_fields[j].1, imported.ident
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    for (i, (name, ty)) in imported_fields.iter().enumerate() {
+        if !local
+            .fields
+            .iter()
+            .enumerate()
+            .any(|(j, field)| field_name(field, j) == *name)
+        {
+            errors.push(Error::new(
+                local.ident.span(),
+                format!(
+                    "`{}` is missing field {} of `{}`, `{name}: {ty}`",
+                    local.ident,
+                    i + 1,
+                    imported.ident
+                ),
+            ));
+        }
+    }
+    errors
+}
+
+/// Reports a difference if the names both sides have in common appear in a different order.
+fn compare_order(
+    what: &str,
+    left: impl Iterator<Item = String>,
+    right: impl Iterator<Item = String>,
+) -> Option<String> {
+    let left: Vec<String> = left.collect();
+    let right: Vec<String> = right.collect();
+    let left_common: Vec<&String> = left.iter().filter(|name| right.contains(name)).collect();
+    let right_common: Vec<&String> = right.iter().filter(|name| left.contains(name)).collect();
+    if left_common == right_common {
+        return None;
+    }
+    Some(format!(
+        "{what}: {} vs {}",
+        left.join(", "),
+        right.join(", ")
+    ))
+}
+
+/// Names a field after its ident, or its index for tuple fields.
+pub fn field_name(field: &Field, index: usize) -> String {
+    match &field.ident {
+        Some(ident) => ident.to_string(),
+        None => index.to_string(),
+    }
+}
+
+fn fields_style(fields: &Fields) -> &'static str {
+    match fields {
+        Fields::Named(_) => "named",
+        Fields::Unnamed(_) => "tuple",
+        Fields::Unit => "unit",
+    }
+}
+
+/// Names the kind of an item the way it is written in source, i.e. `struct` or `fn`.
+pub fn kind(item: &Item) -> &'static str {
+    match item {
+        Item::Struct(_) => "struct",
+        Item::Enum(_) => "enum",
+        Item::Union(_) => "union",
+        Item::Trait(_) => "trait",
+        Item::Fn(_) => "fn",
+        Item::Const(_) => "const",
+        Item::Static(_) => "static",
+        Item::Type(_) => "type",
+        Item::Mod(_) => "mod",
+        Item::Impl(_) => "impl",
+        Item::Use(_) => "use",
+        _ => "item",
+    }
+}
@@ -0,0 +1,239 @@
+//! Generates forwarding trait impls for `#[delegate_trait]`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    parse_quote, Error, FnArg, GenericParam, Generics, Ident, Item, ItemStruct, ItemTrait, Member,
+    Pat, Path, Result, Signature, Token, TraitItem, Type,
+};
+
+use crate::{compare, path_string, Roots};
+
+/// Arguments to `#[delegate_trait]`: the path of an `#[export_tokens]` trait, which must also
+/// name the trait itself, followed by `to = field`.
+pub struct DelegateArgs {
+    path: Path,
+    to: Member,
+}
+
+impl Parse for DelegateArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let key: Ident = input.parse()?;
+        if key != "to" {
+            return Err(Error::new(key.span(), "expected `to = field`"));
+        }
+        input.parse::<Token![=]>()?;
+        let to = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(DelegateArgs { path, to })
+    }
+}
+
+impl DelegateArgs {
+    /// Expands to a `forward_tokens!` call that hands the trait to `inner_macro`, passing the
+    /// trait path, the field and the wrapper struct along as `{ path; field; struct }`.
+    pub fn forward(self, wrapper: ItemStruct, inner_macro: &str, roots: &Roots) -> TokenStream2 {
+        let DelegateArgs { path, to } = self;
+        let inner_macro = Ident::new(inner_macro, Span::call_site());
+        let mm_path = &roots.macro_magic;
+        quote! {
+            #mm_path::forward_tokens! {
+                #path,
+                #inner_macro,
+                #mm_path,
+                { #path; #to; #wrapper }
+            }
+        }
+    }
+}
+
+/// What the hidden inner macro of `#[delegate_trait]` receives.
+pub struct ForwardedDelegate {
+    pub item: Item,
+    pub path: Path,
+    pub to: Member,
+    pub wrapper: ItemStruct,
+}
+
+impl Parse for ForwardedDelegate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let path = extra.parse()?;
+        extra.parse::<Token![;]>()?;
+        let to = extra.parse()?;
+        extra.parse::<Token![;]>()?;
+        let wrapper = extra.parse()?;
+        Ok(ForwardedDelegate {
+            item,
+            path,
+            to,
+            wrapper,
+        })
+    }
+}
+
+impl ForwardedDelegate {
+    /// Generates an impl of the trait for the wrapper that forwards every associated const,
+    /// type and method to the `to` field, which is required to implement the trait too.
+    pub fn delegate(&self) -> Result<TokenStream2> {
+        let ForwardedDelegate {
+            path, to, wrapper, ..
+        } = self;
+        let trait_item = self.trait_item()?;
+        let field_ty = self.field_ty()?;
+        let mut items = Vec::new();
+        for item in &trait_item.items {
+            items.push(match item {
+                TraitItem::Const(item) => {
+                    let ident = &item.ident;
+                    let ty = &item.ty;
+                    quote!(const #ident: #ty = <#field_ty as #path>::#ident;)
+                }
+                TraitItem::Type(item) => {
+                    let ident = &item.ident;
+                    let generics = &item.generics;
+                    let args = generic_args(generics);
+                    let where_clause = &generics.where_clause;
+                    quote!(type #ident #generics = <#field_ty as #path>::#ident #args #where_clause;)
+                }
+                TraitItem::Fn(item) => {
+                    let (sig, args) = forwarded_signature(&item.sig);
+                    let method = &sig.ident;
+                    let receiver = match sig.receiver() {
+                        Some(receiver) if receiver.reference.is_none() => quote!(self.#to,),
+                        Some(receiver) if receiver.mutability.is_some() => {
+                            quote!(&mut self.#to,)
+                        }
+                        Some(_) => quote!(&self.#to,),
+                        None => quote!(),
+                    };
+                    let mut call = quote!(<#field_ty as #path>::#method(#receiver #(#args),*));
+                    if sig.asyncness.is_some() {
+                        call = quote!(#call.await);
+                    }
+                    if sig.unsafety.is_some() {
+                        call = quote!(unsafe { #call });
+                    }
+                    quote!(#sig { #call })
+                }
+                item => {
+                    return Err(Error::new(
+                        wrapper.ident.span(),
+                        format!(
+                            "delegate_trait can't forward `{}` in `{}`",
+                            item.to_token_stream(),
+                            trait_item.ident
+                        ),
+                    ))
+                }
+            });
+        }
+        let mut generics = wrapper.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#field_ty: #path));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let ident = &wrapper.ident;
+        Ok(quote! {
+            #wrapper
+
+            impl #impl_generics #path for #ident #ty_generics #where_clause {
+                #(#items)*
+            }
+        })
+    }
+
+    fn trait_item(&self) -> Result<&ItemTrait> {
+        match &self.item {
+            Item::Trait(item) if item.generics.params.is_empty() => Ok(item),
+            Item::Trait(item) => Err(Error::new(
+                self.wrapper.ident.span(),
+                format!(
+                    "delegate_trait doesn't support generic traits like `{}` yet",
+                    item.ident
+                ),
+            )),
+            item => Err(Error::new(
+                self.wrapper.ident.span(),
+                format!(
+                    "delegate_trait expects a trait, but `{}` is a {}",
+                    path_string(&self.path),
+                    compare::kind(item)
+                ),
+            )),
+        }
+    }
+
+    /// The type of the field calls are forwarded to.
+    fn field_ty(&self) -> Result<&Type> {
+        self.wrapper
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(i, field)| match (&self.to, &field.ident) {
+                (Member::Named(to), Some(ident)) => to == ident,
+                (Member::Unnamed(to), None) => to.index as usize == *i,
+                _ => false,
+            })
+            .map(|(_, field)| &field.ty)
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    &self.to,
+                    format!(
+                        "`{}` has no field `{}`",
+                        self.wrapper.ident,
+                        self.to.to_token_stream()
+                    ),
+                )
+            })
+    }
+}
+
+/// Turns the parameters of an associated type into the matching arguments, i.e.
+/// `<'a, T>` for `type Item<'a, T: Clone>`.
+fn generic_args(generics: &Generics) -> TokenStream2 {
+    if generics.params.is_empty() {
+        return quote!();
+    }
+    let args = generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Const(param) => param.ident.to_token_stream(),
+    });
+    quote!(<#(#args),*>)
+}
+
+/// Prepares a trait method signature for an impl that passes its arguments on to another
+/// function, renaming arguments that aren't plain identifiers (i.e. `_` or tuple patterns) to
+/// `__argN` and giving the receiver a `self` the body can use. Returns the signature along
+/// with the arguments, not counting the receiver.
+pub fn forwarded_signature(sig: &Signature) -> (Signature, Vec<Ident>) {
+    let mut sig = sig.clone();
+    let mut args = Vec::new();
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        let input = match input {
+            FnArg::Receiver(receiver) => {
+                // the imported `self` comes out of a `macro_rules!` expansion, so its hygiene
+                // wouldn't let the body we generate refer to it
+                receiver.self_token = Token![self](Span::call_site());
+                continue;
+            }
+            FnArg::Typed(input) => input,
+        };
+        let ident = match &*input.pat {
+            Pat::Ident(pat) if pat.subpat.is_none() && pat.ident != "_" => pat.ident.clone(),
+            _ => format_ident!("__arg{i}"),
+        };
+        input.pat = parse_quote!(#ident);
+        args.push(ident);
+    }
+    (sig, args)
+}
@@ -0,0 +1,125 @@
+//! Collects doc comments for `item_docs!`.
+
+use export_items::item_attrs;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Attribute, Expr, ExprLit, Fields, Item, Lit, Meta, Path};
+
+use crate::compare;
+
+/// Compile-time mirror of `types_crate::ItemDocs`, whose [`Docs::expr`] is the equivalent
+/// constant expression.
+pub struct Docs {
+    item: String,
+    fields: Vec<FieldDocs>,
+    variants: Vec<VariantDocs>,
+}
+
+/// Compile-time mirror of `types_crate::FieldDocs`.
+struct FieldDocs {
+    name: String,
+    docs: String,
+}
+
+/// Compile-time mirror of `types_crate::VariantDocs`.
+struct VariantDocs {
+    name: String,
+    docs: String,
+    fields: Vec<FieldDocs>,
+}
+
+/// Collects the docs of an item along with those of its fields or variants.
+pub fn item_docs(item: &Item) -> Docs {
+    Docs {
+        item: docs(item_attrs(item)),
+        fields: match item {
+            Item::Struct(item) => fields_docs(&item.fields),
+            Item::Union(item) => fields_docs(&Fields::Named(item.fields.clone())),
+            _ => Vec::new(),
+        },
+        variants: match item {
+            Item::Enum(item) => item
+                .variants
+                .iter()
+                .map(|variant| VariantDocs {
+                    name: variant.ident.to_string(),
+                    docs: docs(&variant.attrs),
+                    fields: fields_docs(&variant.fields),
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn fields_docs(fields: &Fields) -> Vec<FieldDocs> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| FieldDocs {
+            name: compare::field_name(field, i),
+            docs: docs(&field.attrs),
+        })
+        .collect()
+}
+
+/// Joins the lines of the `#[doc = "..."]` attributes in `attrs`, dropping the space
+/// `///` comments leave at the start of each line. Docs that aren't string literals, like
+/// `#[doc = include_str!("..")]`, are skipped.
+fn docs(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Docs {
+    /// The `types_crate::ItemDocs` expression, with `types_crate` at the path given.
+    pub fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let item = &self.item;
+        let fields = self.fields.iter().map(|field| field.expr(types_crate));
+        let variants = self
+            .variants
+            .iter()
+            .map(|variant| variant.expr(types_crate));
+        quote! {
+            #types_crate::ItemDocs {
+                item: #item,
+                fields: &[#(#fields),*],
+                variants: &[#(#variants),*],
+            }
+        }
+    }
+}
+
+impl FieldDocs {
+    fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let name = &self.name;
+        let docs = &self.docs;
+        quote! {
+            #types_crate::FieldDocs { name: #name, docs: #docs }
+        }
+    }
+}
+
+impl VariantDocs {
+    fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let name = &self.name;
+        let docs = &self.docs;
+        let fields = self.fields.iter().map(|field| field.expr(types_crate));
+        quote! {
+            #types_crate::VariantDocs { name: #name, docs: #docs, fields: &[#(#fields),*] }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Copies the variants of an imported enum into a local one for `#[extend_enum]`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    Error, Fields, GenericParam, Ident, Item, ItemEnum, Path, Result, Token,
+};
+
+use crate::{compare, Roots};
+
+/// Arguments to `#[extend_enum]`: the path of an `#[export_tokens]` enum, optionally followed
+/// by `from` if that path also names the enum itself, or `from = path` if it lives elsewhere.
+pub struct ExtendArgs {
+    path: Path,
+    from: Option<Path>,
+}
+
+impl Parse for ExtendArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path: Path = input.parse()?;
+        let mut from = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "from" {
+                return Err(Error::new(key.span(), "expected `from` or `from = path`"));
+            }
+            from = match input.parse::<Option<Token![=]>>()? {
+                Some(_) => Some(input.parse()?),
+                None => Some(path.clone()),
+            };
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(ExtendArgs { path, from })
+    }
+}
+
+impl ExtendArgs {
+    /// Expands to a `forward_tokens!` call that hands the enum to `inner_macro`, passing the
+    /// path of the upstream enum, if any, and the local enum along as `{ [from]; enum }`.
+    pub fn forward(self, local: ItemEnum, inner_macro: &str, roots: &Roots) -> TokenStream2 {
+        let ExtendArgs { path, from } = self;
+        let from = from.into_iter();
+        let inner_macro = Ident::new(inner_macro, Span::call_site());
+        let mm_path = &roots.macro_magic;
+        quote! {
+            #mm_path::forward_tokens! {
+                #path,
+                #inner_macro,
+                #mm_path,
+                { [#(#from)*]; #local }
+            }
+        }
+    }
+}
+
+/// What the hidden inner macro of `#[extend_enum]` receives.
+pub struct ForwardedExtend {
+    pub item: Item,
+    pub from: Option<Path>,
+    pub local: ItemEnum,
+}
+
+impl Parse for ForwardedExtend {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let from;
+        bracketed!(from in extra);
+        let from = match from.is_empty() {
+            true => None,
+            false => Some(from.parse()?),
+        };
+        extra.parse::<Token![;]>()?;
+        let local = extra.parse()?;
+        Ok(ForwardedExtend { item, from, local })
+    }
+}
+
+impl ForwardedExtend {
+    /// Emits the local enum with the upstream variants prepended, followed by either a `From`
+    /// impl converting the upstream enum, or an `UPSTREAM_VARIANTS` constant listing the names
+    /// of the upstream variants.
+    pub fn extend(self) -> Result<TokenStream2> {
+        let ForwardedExtend { item, from, local } = self;
+        let upstream = match item {
+            Item::Enum(upstream) => upstream,
+            item => {
+                return Err(Error::new(
+                    local.ident.span(),
+                    format!(
+                        "extend_enum expects an enum, not a {}",
+                        compare::kind(&item)
+                    ),
+                ))
+            }
+        };
+        let mut errors: Option<Error> = None;
+        for variant in &local.variants {
+            if upstream.variants.iter().any(|v| v.ident == variant.ident) {
+                let err = Error::new(
+                    variant.ident.span(),
+                    format!(
+                        "`{}` is already a variant of the upstream `{}`",
+                        variant.ident, upstream.ident
+                    ),
+                );
+                match &mut errors {
+                    Some(errors) => errors.combine(err),
+                    None => errors = Some(err),
+                }
+            }
+        }
+        if let Some(errors) = errors {
+            return Err(errors);
+        }
+        let mut extended = local.clone();
+        if extended.generics.params.is_empty() {
+            extended.generics = upstream.generics.clone();
+        }
+        let declared: Vec<String> = extended.generics.params.iter().map(param_name).collect();
+        let missing: Vec<String> = upstream
+            .generics
+            .params
+            .iter()
+            .map(param_name)
+            .filter(|name| !declared.contains(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::new(
+                local.ident.span(),
+                format!(
+                    "`{}` must declare the generic parameters of the upstream `{}`, missing `{}`",
+                    local.ident,
+                    upstream.ident,
+                    missing.join("`, `")
+                ),
+            ));
+        }
+        let (_, upstream_generics, _) = upstream.generics.split_for_impl();
+        extended.variants = upstream
+            .variants
+            .iter()
+            .cloned()
+            .chain(local.variants)
+            .collect();
+        let (impl_generics, ty_generics, where_clause) = extended.generics.split_for_impl();
+        let ident = &extended.ident;
+        let extra = match from {
+            Some(from) => {
+                let arms = upstream.variants.iter().map(|variant| {
+                    let name = &variant.ident;
+                    match &variant.fields {
+                        Fields::Unit => quote!(#from::#name => Self::#name),
+                        Fields::Named(fields) => {
+                            let fields = fields.named.iter().map(|f| &f.ident);
+                            let bindings = fields.clone();
+                            quote!(#from::#name { #(#fields),* } => Self::#name { #(#bindings),* })
+                        }
+                        Fields::Unnamed(fields) => {
+                            let bindings: Vec<Ident> = (0..fields.unnamed.len())
+                                .map(|i| format_ident!("__{i}"))
+                                .collect();
+                            quote!(#from::#name(#(#bindings),*) => Self::#name(#(#bindings),*))
+                        }
+                    }
+                });
+                quote! {
+                    impl #impl_generics ::core::convert::From<#from #upstream_generics>
+                        for #ident #ty_generics #where_clause
+                    {
+                        fn from(value: #from #upstream_generics) -> Self {
+                            match value {
+                                #(#arms,)*
+                            }
+                        }
+                    }
+                }
+            }
+            None => {
+                let names = upstream.variants.iter().map(|v| v.ident.to_string());
+                let doc = format!(
+                    "The names of the variants copied from `{}`, in order.",
+                    upstream.ident
+                );
+                quote! {
+                    impl #impl_generics #ident #ty_generics #where_clause {
+                        #[doc = #doc]
+                        pub const UPSTREAM_VARIANTS: &'static [&'static str] = &[#(#names),*];
+                    }
+                }
+            }
+        };
+        Ok(quote! {
+            #extended
+            #extra
+        })
+    }
+}
+
+/// The name a generic parameter is referred to by, i.e. `T`, `'a` or `N`.
+fn param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(param) => param.ident.to_string(),
+        GenericParam::Lifetime(param) => param.lifetime.to_string(),
+        GenericParam::Const(param) => param.ident.to_string(),
+    }
+}
@@ -0,0 +1,134 @@
+//! Argument handling shared by `field_names!`, `field_types!` and `module_item_src!`.
+
+use macro_magic::mm_core::to_snake_case;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    Error, Fields, Ident, Item, Path, Result, Token,
+};
+
+use crate::{compare, default_const_name, Roots};
+
+/// Arguments to `field_names!` and `field_types!`: the path of an `#[export_tokens]` struct or
+/// enum, the name of a variant if it is an enum, and an optional `as NAME`. `module_item_src!`
+/// takes the same arguments, with the name of an item of the module in place of the variant.
+pub struct FieldListArgs {
+    path: Path,
+    /// The variant to list the fields of, or the item of the module to emit.
+    member: Option<Ident>,
+    name: Option<Ident>,
+}
+
+impl Parse for FieldListArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let member = match input.parse::<Option<Token![,]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        let name = match input.parse::<Option<Token![as]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        Ok(FieldListArgs { path, member, name })
+    }
+}
+
+impl FieldListArgs {
+    /// Expands to a `forward_tokens!` call that hands the item to `inner_macro`, passing the
+    /// name of the constant to emit and the variant or module item, if any, as `{ NAME }` or
+    /// `{ NAME; Member }`.
+    pub fn forward(self, inner_macro: &str, suffix: &str, roots: &Roots) -> TokenStream2 {
+        let suffix = match &self.member {
+            Some(member) => format!("{}_{suffix}", upper_snake_case(member)),
+            None => suffix.to_string(),
+        };
+        let name = self
+            .name
+            .unwrap_or_else(|| default_const_name(&self.path, &suffix));
+        let path = self.path;
+        let member = self.member.map(|member| quote!(; #member));
+        let inner_macro = Ident::new(inner_macro, Span::call_site());
+        let mm_path = &roots.macro_magic;
+        quote! {
+            #mm_path::forward_tokens! {
+                #path,
+                #inner_macro,
+                #mm_path,
+                { #name #member }
+            }
+        }
+    }
+}
+
+/// Upper snake case of a variant or item name, i.e. `RECT` for `Rect`.
+fn upper_snake_case(member: &Ident) -> String {
+    to_snake_case(member.to_string()).to_uppercase()
+}
+
+/// What the hidden inner macros of `field_names!`, `field_types!` and `module_item_src!`
+/// receive.
+pub struct ForwardedFieldList {
+    pub item: Item,
+    pub name: Ident,
+    /// The variant to list the fields of, or the item of the module to emit.
+    pub member: Option<Ident>,
+}
+
+impl Parse for ForwardedFieldList {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let name = extra.parse()?;
+        let member = match extra.parse::<Option<Token![;]>>()? {
+            Some(_) => Some(extra.parse()?),
+            None => None,
+        };
+        Ok(ForwardedFieldList { item, name, member })
+    }
+}
+
+impl ForwardedFieldList {
+    /// Finds the fields of the struct, or of the requested variant of the enum.
+    pub fn fields(&self) -> Result<&Fields> {
+        match (&self.item, &self.member) {
+            (Item::Struct(item), None) => Ok(&item.fields),
+            (Item::Enum(item), Some(variant)) => item
+                .variants
+                .iter()
+                .find(|v| v.ident == *variant)
+                .map(|v| &v.fields)
+                .ok_or_else(|| {
+                    Error::new(
+                        variant.span(),
+                        format!("`{}` has no variant named `{variant}`", item.ident),
+                    )
+                }),
+            (Item::Enum(item), None) => Err(Error::new(
+                self.name.span(),
+                format!(
+                    "`{}` is an enum, name the variant whose fields you want after the path, \
+                     e.g. `{}, {}`",
+                    item.ident,
+                    item.ident,
+                    item.variants
+                        .first()
+                        .map(|v| v.ident.to_string())
+                        .unwrap_or_else(|| "Variant".to_string())
+                ),
+            )),
+            (Item::Struct(item), Some(variant)) => Err(Error::new(
+                variant.span(),
+                format!("`{}` is a struct, it has no variants", item.ident),
+            )),
+            (item, _) => Err(Error::new(
+                self.name.span(),
+                format!("expected a struct or enum, not a {}", compare::kind(item)),
+            )),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Stable fingerprints of item tokens, used by `item_fingerprint!` and
+//! `assert_item_fingerprint!`.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::Item;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Returns the fingerprint of `item` as 16 lowercase hex digits.
+///
+/// The fingerprint is the 64-bit FNV-1a hash of [`normalize`]d tokens, so it only changes when
+/// the tokens of the item change, not when whitespace or `//` comments do, and it doesn't
+/// depend on how the current `proc-macro2` happens to print a `TokenStream`. Doc comments are
+/// `#[doc]` attributes, so changing them does change the fingerprint.
+pub fn fingerprint(item: &Item) -> String {
+    let mut normalized = String::new();
+    normalize(item.to_token_stream(), &mut normalized);
+    let hash = normalized.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    });
+    format!("{hash:016x}")
+}
+
+/// Writes `tokens` to `out` separated by single spaces, except that joint punctuation such as
+/// `::` or `->` is written without a space in between.
+fn normalize(tokens: TokenStream2, out: &mut String) {
+    let mut joint = false;
+    for tree in tokens {
+        if !out.is_empty() && !joint {
+            out.push(' ');
+        }
+        joint = false;
+        match tree {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                normalize(group.stream(), out);
+                out.push_str(close);
+            }
+            TokenTree::Ident(ident) => out.push_str(&ident.to_string()),
+            TokenTree::Punct(punct) => {
+                out.push(punct.as_char());
+                joint = punct.spacing() == Spacing::Joint;
+            }
+            TokenTree::Literal(literal) => out.push_str(&literal.to_string()),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Re-emits an imported free function in the calling crate for `inline_foreign_fn!`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    visit_mut::{self, VisitMut},
+    Attribute, Error, ExprPath, Ident, Item, ItemFn, ItemUse, Path, PathSegment, Result, Token,
+    UseTree, Visibility,
+};
+
+use crate::{compare, path_string, Roots};
+
+/// Arguments to `inline_foreign_fn!`: optional outer attributes and visibility, the path of an
+/// `#[export_tokens]` fn and `as name`.
+pub struct InlineArgs {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    path: Path,
+    name: Ident,
+}
+
+impl Parse for InlineArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let path = input.parse()?;
+        input.parse::<Token![as]>()?;
+        let name = input.parse()?;
+        Ok(InlineArgs {
+            attrs,
+            vis,
+            path,
+            name,
+        })
+    }
+}
+
+impl InlineArgs {
+    /// Expands to a `forward_tokens!` call that hands the fn to `inner_macro`, passing the path
+    /// along with an empty fn carrying the attributes, visibility and name the inlined fn
+    /// should get as `{ path; fn }`.
+    pub fn forward(self, inner_macro: &str, roots: &Roots) -> TokenStream2 {
+        let InlineArgs {
+            attrs,
+            vis,
+            path,
+            name,
+        } = self;
+        let inner_macro = Ident::new(inner_macro, Span::call_site());
+        let mm_path = &roots.macro_magic;
+        quote! {
+            #mm_path::forward_tokens! {
+                #path,
+                #inner_macro,
+                #mm_path,
+                { #path; #(#attrs)* #vis fn #name() {} }
+            }
+        }
+    }
+}
+
+/// What the hidden inner macro of `inline_foreign_fn!` receives.
+pub struct ForwardedInline {
+    pub item: Item,
+    pub path: Path,
+    pub template: ItemFn,
+}
+
+impl Parse for ForwardedInline {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let path = extra.parse()?;
+        extra.parse::<Token![;]>()?;
+        let template = extra.parse()?;
+        Ok(ForwardedInline {
+            item,
+            path,
+            template,
+        })
+    }
+}
+
+impl ForwardedInline {
+    /// Emits the imported fn under the name and visibility of the template, with the template's
+    /// attributes added to its own, references to itself renamed, and `crate::` paths pointing
+    /// at the crate it was exported from.
+    pub fn inline(self) -> Result<TokenStream2> {
+        let ForwardedInline {
+            item,
+            path,
+            template,
+        } = self;
+        let name = template.sig.ident;
+        let mut inlined = match item {
+            Item::Fn(item) => item,
+            item => {
+                return Err(Error::new(
+                    name.span(),
+                    format!(
+                        "inline_foreign_fn expects a fn, but `{}` is a {}",
+                        path_string(&path),
+                        compare::kind(&item)
+                    ),
+                ))
+            }
+        };
+        let krate = path.segments.first().unwrap().ident.clone();
+        let mut rewrite = Rewrite {
+            krate,
+            original: inlined.sig.ident.clone(),
+            name: name.clone(),
+            error: None,
+        };
+        rewrite.visit_item_fn_mut(&mut inlined);
+        if let Some(err) = rewrite.error {
+            return Err(err);
+        }
+        inlined.attrs.extend(template.attrs);
+        inlined.vis = template.vis;
+        inlined.sig.ident = name;
+        Ok(quote!(#inlined))
+    }
+}
+
+/// Points paths in the signature and body of an inlined fn back at the crate it came from.
+///
+/// Which module `self` and `super` refer to isn't part of the exported tokens, so `self::` and
+/// `super::` paths can't be resolved and are reported. Resolving them from the crate root
+/// would fail, or worse, find a different item of the same name there.
+struct Rewrite {
+    krate: Ident,
+    original: Ident,
+    name: Ident,
+    error: Option<Error>,
+}
+
+impl Rewrite {
+    fn is_module_relative(ident: &Ident) -> bool {
+        ident == "self" || ident == "super"
+    }
+
+    fn report_module_relative(&mut self, ident: &Ident) {
+        let err = Error::new(
+            self.name.span(),
+            format!(
+                "inline_foreign_fn can't tell which module of `{}` `{ident}` refers to, use a \
+                 `crate::` path in `{}`",
+                self.krate, self.original
+            ),
+        );
+        match &mut self.error {
+            Some(error) => error.combine(err),
+            None => self.error = Some(err),
+        }
+    }
+}
+
+impl VisitMut for Rewrite {
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        if path.leading_colon.is_none() && path.segments.len() > 1 {
+            let first = path.segments[0].ident.clone();
+            if first == "crate" {
+                path.segments[0] = PathSegment::from(self.krate.clone());
+                path.leading_colon = Some(Token![::](Span::call_site()));
+            } else if Self::is_module_relative(&first) {
+                self.report_module_relative(&first);
+            }
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    /// Renames references to the fn itself, whether it is called or passed on as a value like
+    /// in `.map(digits)`.
+    fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+        if expr.qself.is_none() && expr.path.is_ident(&self.original) {
+            expr.path.segments[0].ident = self.name.clone();
+        }
+        visit_mut::visit_expr_path_mut(self, expr);
+    }
+
+    fn visit_item_use_mut(&mut self, item: &mut ItemUse) {
+        if item.leading_colon.is_none() {
+            if let UseTree::Path(tree) = &mut item.tree {
+                if tree.ident == "crate" {
+                    tree.ident = self.krate.clone();
+                    item.leading_colon = Some(Token![::](Span::call_site()));
+                } else if Self::is_module_relative(&tree.ident) {
+                    let ident = tree.ident.clone();
+                    self.report_module_relative(&ident);
+                }
+            }
+        }
+        visit_mut::visit_item_use_mut(self, item);
+    }
+}
@@ -1,20 +1,1796 @@
-use macro_magic::import_tokens_proc;
+use std::{io, path::PathBuf};
+
+use macro_magic::mm_core::to_snake_case;
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{
+    braced, bracketed, parenthesized,
+    parse::{Parse, ParseStream, Parser},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    token::Bracket,
+    Attribute, Expr, ExprLit, Field, Fields, Ident, Item, ItemEnum, ItemImpl, ItemMod, ItemStruct,
+    Lit, LitStr, Path, Result, Token, Visibility,
+};
+
+use catalog::CatalogEntry;
+use chain::{Chain, ForwardedChain};
+use delegate::{DelegateArgs, ForwardedDelegate};
+use extend::{ExtendArgs, ForwardedExtend};
+use fields::{FieldListArgs, ForwardedFieldList};
+use inline::{ForwardedInline, InlineArgs};
+
+mod catalog;
+mod cfg;
+mod chain;
+mod compare;
+mod delegate;
+mod docs;
+mod extend;
+mod fields;
+mod fingerprint;
+mod inline;
+mod json;
+mod meta;
+mod mock;
+mod pretty;
+mod schema;
+#[cfg(test)]
+mod snapshots;
+
+/// Arguments to [`make_item_const`] and [`make_item_source`]: the path of an
+/// `#[export_tokens]` item, optionally followed by `as NAME` to choose the name of the emitted
+/// constant.
+struct NamedItemArgs {
+    path: Path,
+    name: Option<Ident>,
+    cfg: bool,
+}
+
+impl Parse for NamedItemArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let name = match input.parse::<Option<Token![as]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        Ok(NamedItemArgs {
+            path,
+            name,
+            cfg: false,
+        })
+    }
+}
+
+impl NamedItemArgs {
+    /// Like [`NamedItemArgs::parse`], but also accepts a trailing `, cfg` asking for the
+    /// `#[cfg]` attributes of the item to be evaluated with the features of the calling crate.
+    fn parse_with_cfg(input: ParseStream) -> Result<Self> {
+        let mut args: NamedItemArgs = input.parse()?;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let flag: Ident = input.parse()?;
+            if flag != "cfg" {
+                return Err(syn::Error::new(flag.span(), "expected `cfg`"));
+            }
+            args.cfg = true;
+        }
+        Ok(args)
+    }
+}
+
+/// What the hidden inner macros of [`make_item_const`] and [`make_item_source`] receive once
+/// `forward_tokens!` has resolved the foreign item: the item itself followed by the `$extra`
+/// block we passed along, which holds the name of the constant to emit and, if `, cfg` was
+/// given, `; cfg`.
+struct ForwardedNamedItem {
+    item: Item,
+    name: Ident,
+    cfg: bool,
+}
+
+impl Parse for ForwardedNamedItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let name = extra.parse()?;
+        let cfg = extra.parse::<Option<Token![;]>>()?.is_some();
+        if cfg {
+            extra.parse::<Ident>()?;
+        }
+        Ok(ForwardedNamedItem { item, name, cfg })
+    }
+}
+
+impl ForwardedNamedItem {
+    /// Emits whatever `emit` generates for the item, first configuring it with the features of
+    /// the calling crate if `, cfg` was given. See the `cfg` module for how.
+    fn emit(self, emit: impl Fn(&Item, &Ident) -> TokenStream2) -> TokenStream2 {
+        let ForwardedNamedItem { item, name, cfg } = self;
+        if !cfg {
+            return emit(&item, &name);
+        }
+        cfg::per_cfg(&item, |item| emit(item, &name)).unwrap_or_else(|err| err.to_compile_error())
+    }
+}
+
+/// The paths our expansions refer to `macro_magic` and `types_crate` by, the first of which is
+/// our `MACRO_MAGIC_ROOT` override.
+///
+/// Each macro has two entry points that differ only in these: the public one refers to
+/// `::macro_magic` and `::types_crate`, which the calling crate depends on, while the hidden
+/// `__facade_*` one that `facade_crate` re-exports under the public name refers to the copies
+/// `facade_crate` re-exports, so crates using our macros through it don't need those
+/// dependencies themselves. Setting the `MACRO_MAGIC_ROOT` environment variable instead would
+/// also change the paths the code `macro_magic` generates inside this crate uses, and this
+/// crate can't depend on `facade_crate`.
+struct Roots {
+    macro_magic: Path,
+    types_crate: Path,
+}
+
+impl Roots {
+    /// The roots of a crate that depends on `macro_magic` and `types_crate` itself.
+    fn direct() -> Self {
+        Roots {
+            macro_magic: parse_quote!(::macro_magic),
+            types_crate: parse_quote!(::types_crate),
+        }
+    }
 
-#[import_tokens_proc]
+    /// The roots of a crate that uses our macros through `facade_crate`.
+    fn facade() -> Self {
+        Roots {
+            macro_magic: parse_quote!(::facade_crate::macro_magic),
+            types_crate: parse_quote!(::facade_crate::types_crate),
+        }
+    }
+}
+
+/// Turns what one of the `*_internal` functions behind our macros returns into the output of
+/// the macro.
+fn expand(result: Result<TokenStream2>) -> TokenStream {
+    result.unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+/// Derives a constant name from the export name of an item, i.e. `StructTwo` with a `suffix`
+/// of `SRC` becomes `STRUCT_TWO_SRC`. Export names that are already upper snake case, like
+/// those of consts and statics, are kept as they are.
+fn default_const_name(path: &Path, suffix: &str) -> Ident {
+    let export_name = &path.segments.last().unwrap().ident;
+    let export_name_str = export_name.to_string();
+    let snake_name = match export_name_str.chars().any(|c| c.is_lowercase()) {
+        true => to_snake_case(export_name_str).to_uppercase(),
+        false => export_name_str,
+    };
+    Ident::new(&format!("{snake_name}_{suffix}"), export_name.span())
+}
+
+/// Renders a path the way it would be written by hand, i.e. `foreign_crate::StructTwo`.
+fn path_string(path: &Path) -> String {
+    path.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Expands to a `forward_tokens!` call that hands the item at `args.path` to `inner_macro`,
+/// passing the name of the constant to emit through the `$extra` arm of the
+/// `__export_tokens_tt_*` macro.
+fn forward_named_item(
+    args: NamedItemArgs,
+    inner_macro: &str,
+    suffix: &str,
+    roots: &Roots,
+) -> TokenStream2 {
+    let name = args
+        .name
+        .unwrap_or_else(|| default_const_name(&args.path, suffix));
+    let source_path = args.path;
+    let cfg = args.cfg.then(|| quote!(; cfg));
+    let inner_macro = Ident::new(inner_macro, Span::call_site());
+    let mm_path = &roots.macro_magic;
+    quote! {
+        #mm_path::forward_tokens! {
+            #source_path,
+            #inner_macro,
+            #mm_path,
+            { #name #cfg }
+        }
+    }
+}
+
+/// Emits a `const &'static str` containing the source code of the `#[export_tokens]` item at
+/// the specified path.
+///
+/// The constant is named after the export name of the item (`foreign_crate::StructTwo` emits
+/// `STRUCT_TWO_SRC`), unless a name is given explicitly:
+///
+/// ```ignore
+/// make_item_const!(foreign_crate::StructTwo as MY_STRUCT_SRC);
+/// ```
+///
+/// The tokens of the item still carry the `#[cfg]` attributes of its fields, variants and so
+/// on, as those are only evaluated once the item is compiled. Add `, cfg` to evaluate them
+/// with the features of the calling crate instead, which drops whatever they configure out:
+///
+/// ```ignore
+/// make_item_const!(foreign_crate::Config, cfg);
+/// ```
+///
+/// [`make_item_source`], [`item_meta`], [`item_docs`], [`item_fingerprint`], [`json_schema`]
+/// and [`list_module_items`] accept `, cfg` too.
+///
+/// Because `#[import_tokens_proc]` can only forward a path, this macro calls `forward_tokens!`
+/// itself and smuggles the constant name through the `$extra` arm of the
+/// `__export_tokens_tt_*` macro. The inner macro keeps the name `#[import_tokens_proc]` would
+/// have given it so that `#[macro_magic::use_proc]` still imports it.
 #[proc_macro]
 pub fn make_item_const(tokens: TokenStream) -> TokenStream {
-    let item_str = tokens.to_string();
+    expand(make_item_const_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_make_item_const(tokens: TokenStream) -> TokenStream {
+    expand(make_item_const_internal(tokens.into(), &Roots::facade()))
+}
+
+fn make_item_const_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_make_item_const_inner",
+        "SRC",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_make_item_const_inner(tokens: TokenStream) -> TokenStream {
+    expand(make_item_const_inner_internal(tokens.into()))
+}
+
+fn make_item_const_inner_internal(tokens: TokenStream2) -> Result<TokenStream2> {
+    let forwarded: ForwardedNamedItem = syn::parse2(tokens)?;
+    Ok(forwarded.emit(|item, name| {
+        let item_str = item.to_token_stream().to_string();
+        quote! {
+            const #name: &'static str = #item_str;
+        }
+    }))
+}
+
+/// Arguments to [`make_items_const`], either a list of `path [as NAME]` entries, or a list of
+/// paths in brackets optionally followed by `as NAME`.
+enum MakeItemsConstArgs {
+    Named(Vec<NamedItemArgs>),
+    Array(Vec<Path>, Option<Ident>),
+}
+
+impl Parse for MakeItemsConstArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Bracket) {
+            let paths;
+            bracketed!(paths in input);
+            let paths = Punctuated::<Path, Token![,]>::parse_terminated(&paths)?;
+            let name = match input.parse::<Option<Token![as]>>()? {
+                Some(_) => Some(input.parse()?),
+                None => None,
+            };
+            return Ok(MakeItemsConstArgs::Array(paths.into_iter().collect(), name));
+        }
+        let entries = Punctuated::<NamedItemArgs, Token![,]>::parse_terminated(input)?;
+        Ok(MakeItemsConstArgs::Named(entries.into_iter().collect()))
+    }
+}
+
+/// Like [`make_item_const`], but imports several items in one invocation. Given a list of
+/// paths, each optionally followed by `as NAME`, it emits one constant per item, named the
+/// same way [`make_item_const`] would name it:
+///
+/// ```ignore
+/// make_items_const!(foreign_crate::StructOne, foreign_crate::StructTwo as TWO_SRC);
+/// ```
+///
+/// Given a list of paths in brackets it instead emits a single `&[&str]` with the source of
+/// every item in order, named `ITEMS_SRC` unless a name is given with `as NAME`:
+///
+/// ```ignore
+/// make_items_const!([foreign_crate::StructOne, foreign_crate::StructTwo] as MY_STRUCTS);
+/// ```
+///
+/// The items are imported one after the other by chaining `forward_tokens!` calls, so the
+/// hidden inner macro sees all of them together once the last one has been resolved.
+#[proc_macro]
+pub fn make_items_const(tokens: TokenStream) -> TokenStream {
+    expand(make_items_const_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_make_items_const(tokens: TokenStream) -> TokenStream {
+    expand(make_items_const_internal(tokens.into(), &Roots::facade()))
+}
+
+fn make_items_const_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args: MakeItemsConstArgs = syn::parse2(tokens)?;
+    let (paths, payload): (Vec<Path>, Expr) = match args {
+        MakeItemsConstArgs::Named(entries) => {
+            let names = entries
+                .iter()
+                .map(|entry| match &entry.name {
+                    Some(name) => name.clone(),
+                    None => default_const_name(&entry.path, "SRC"),
+                })
+                .collect::<Vec<_>>();
+            let paths = entries.into_iter().map(|entry| entry.path).collect();
+            (paths, parse_quote!((#(#names,)*)))
+        }
+        MakeItemsConstArgs::Array(paths, name) => {
+            let name = name.unwrap_or_else(|| Ident::new("ITEMS_SRC", Span::call_site()));
+            (paths, parse_quote!(#name))
+        }
+    };
+    if paths.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "expected at least one path",
+        ));
+    }
+    Ok(Chain::new(paths, payload).forward("__import_tokens_proc_make_items_const_inner", roots))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_make_items_const_inner(tokens: TokenStream) -> TokenStream {
+    expand(make_items_const_inner_internal(
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_proc_make_items_const_inner(tokens: TokenStream) -> TokenStream {
+    expand(make_items_const_inner_internal(
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn make_items_const_inner_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let ForwardedChain(chain) = syn::parse2(tokens)?;
+    if !chain.remaining.is_empty() {
+        return Ok(chain.forward("__import_tokens_proc_make_items_const_inner", roots));
+    }
+    let item_strs = chain
+        .collected
+        .iter()
+        .map(|item| item.to_token_stream().to_string());
+    Ok(match chain.payload {
+        Expr::Tuple(names) => {
+            let names = names.elems.iter();
+            quote! {
+                #(const #names: &'static str = #item_strs;)*
+            }
+        }
+        name => quote! {
+            const #name: &'static [&'static str] = &[#(#item_strs),*];
+        },
+    })
+}
+
+/// Like [`make_item_const`], but the emitted constant contains the item pretty-printed the
+/// way `rustfmt` would format it rather than the raw output of `TokenStream::to_string()`,
+/// whose spacing is not guaranteed to be stable across `proc-macro2` releases.
+///
+/// The default constant name uses a `_SOURCE` suffix so that both macros can be called on the
+/// same item in the same module:
+///
+/// ```ignore
+/// make_item_source!(foreign_crate::StructTwo);
+///
+/// assert_eq!(STRUCT_TWO_SOURCE, "struct MyStruct {\n    field1: bool,\n}");
+/// ```
+#[proc_macro]
+pub fn make_item_source(tokens: TokenStream) -> TokenStream {
+    expand(make_item_source_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_make_item_source(tokens: TokenStream) -> TokenStream {
+    expand(make_item_source_internal(tokens.into(), &Roots::facade()))
+}
+
+fn make_item_source_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_make_item_source_inner",
+        "SOURCE",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_make_item_source_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedNamedItem);
+    forwarded
+        .emit(|item, name| {
+            let item_src = pretty::item(item);
+            quote! {
+                const #name: &'static str = #item_src;
+            }
+        })
+        .into()
+}
+
+/// Emits a `static` `types_crate::ItemMeta` describing the
+/// `#[export_tokens]` item at the specified path: its kind, ident, generics, visibility,
+/// attributes, and the names and types of its fields, variants, parameters or associated
+/// items, depending on the kind of item.
+///
+/// Like [`make_item_const`], the static is named after the export name of the item
+/// (`foreign_crate::StructOne` emits `STRUCT_ONE_META`) unless a name is given with `as NAME`.
+/// The calling crate must depend on `types_crate`, or use the macro through `facade_crate`,
+/// which re-exports it.
+#[proc_macro]
+pub fn item_meta(tokens: TokenStream) -> TokenStream {
+    expand(item_meta_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_item_meta(tokens: TokenStream) -> TokenStream {
+    expand(item_meta_internal(tokens.into(), &Roots::facade()))
+}
+
+fn item_meta_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_item_meta_inner",
+        "META",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_item_meta_inner(tokens: TokenStream) -> TokenStream {
+    expand(item_meta_inner_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_proc_item_meta_inner(tokens: TokenStream) -> TokenStream {
+    expand(item_meta_inner_internal(tokens.into(), &Roots::facade()))
+}
+
+fn item_meta_inner_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let forwarded: ForwardedNamedItem = syn::parse2(tokens)?;
+    let types_crate = &roots.types_crate;
+    Ok(forwarded.emit(|item, name| {
+        let meta = meta::item_meta(item).expr(types_crate);
+        quote! {
+            static #name: #types_crate::ItemMeta = #meta;
+        }
+    }))
+}
+
+/// Emits a `static` `types_crate::ItemDocs` with the doc comments of the `#[export_tokens]`
+/// item at the specified path, along with those of its fields or variants:
+///
+/// ```ignore
+/// item_docs!(foreign_crate::Shape);
+///
+/// assert_eq!(SHAPE_DOCS.variants[0].docs, "A circle, described by its radius.");
+/// ```
+///
+/// The static is named after the export name of the item, unless a name is given with
+/// `as NAME`.
+#[proc_macro]
+pub fn item_docs(tokens: TokenStream) -> TokenStream {
+    expand(item_docs_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_item_docs(tokens: TokenStream) -> TokenStream {
+    expand(item_docs_internal(tokens.into(), &Roots::facade()))
+}
+
+fn item_docs_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_item_docs_inner",
+        "DOCS",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_item_docs_inner(tokens: TokenStream) -> TokenStream {
+    expand(item_docs_inner_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_proc_item_docs_inner(tokens: TokenStream) -> TokenStream {
+    expand(item_docs_inner_internal(tokens.into(), &Roots::facade()))
+}
+
+fn item_docs_inner_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let forwarded: ForwardedNamedItem = syn::parse2(tokens)?;
+    let types_crate = &roots.types_crate;
+    Ok(forwarded.emit(|item, name| {
+        let docs = docs::item_docs(item).expr(types_crate);
+        quote! {
+            static #name: #types_crate::ItemDocs = #docs;
+        }
+    }))
+}
+
+/// Emits a `const &'static str` containing a stable fingerprint of the tokens of the
+/// `#[export_tokens]` item at the specified path, as 16 hex digits.
+///
+/// The constant is named after the export name of the item (`foreign_crate::StructTwo` emits
+/// `STRUCT_TWO_FINGERPRINT`) unless a name is given with `as NAME`. Whitespace and `//`
+/// comments don't affect the fingerprint, any other change to the item does, including one to
+/// its doc comments, which are `#[doc]` attributes. Pass the value to
+/// [`assert_item_fingerprint`] to pin an item down.
+#[proc_macro]
+pub fn item_fingerprint(tokens: TokenStream) -> TokenStream {
+    expand(item_fingerprint_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_item_fingerprint(tokens: TokenStream) -> TokenStream {
+    expand(item_fingerprint_internal(tokens.into(), &Roots::facade()))
+}
+
+fn item_fingerprint_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_item_fingerprint_inner",
+        "FINGERPRINT",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_item_fingerprint_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedNamedItem);
+    forwarded
+        .emit(|item, name| {
+            let fingerprint = fingerprint::fingerprint(item);
+            quote! {
+                const #name: &'static str = #fingerprint;
+            }
+        })
+        .into()
+}
+
+/// Arguments to [`assert_item_fingerprint`]: the path of an `#[export_tokens]` item followed
+/// by the fingerprint it is expected to have.
+struct FingerprintArgs {
+    path: Path,
+    expected: LitStr,
+}
+
+impl Parse for FingerprintArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let expected = input.parse()?;
+        Ok(FingerprintArgs { path, expected })
+    }
+}
+
+/// What the hidden inner macro of [`assert_item_fingerprint`] receives: the item followed by
+/// an `$extra` block holding the expected fingerprint and the path the item was imported
+/// from, as a `("expected", "path")` tuple.
+struct ForwardedFingerprint {
+    item: Item,
+    expected: LitStr,
+    path: LitStr,
+}
+
+impl Parse for ForwardedFingerprint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let tuple;
+        parenthesized!(tuple in extra);
+        let expected = tuple.parse()?;
+        tuple.parse::<Token![,]>()?;
+        let path = tuple.parse()?;
+        Ok(ForwardedFingerprint {
+            item,
+            expected,
+            path,
+        })
+    }
+}
+
+/// Fails compilation if the fingerprint of the `#[export_tokens]` item at the specified path,
+/// as computed by [`item_fingerprint`], is not the expected one:
+///
+/// ```ignore
+/// assert_item_fingerprint!(foreign_crate::StructTwo, "621995b2909e44f8");
+/// ```
+///
+/// This turns a silent change to a foreign item, even a private one, into a build error in
+/// the crate that depends on it. The error shows both fingerprints and the current definition
+/// of the item so the change can be reviewed before the expected fingerprint is updated.
+#[proc_macro]
+pub fn assert_item_fingerprint(tokens: TokenStream) -> TokenStream {
+    expand(assert_item_fingerprint_internal(
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_assert_item_fingerprint(tokens: TokenStream) -> TokenStream {
+    expand(assert_item_fingerprint_internal(
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn assert_item_fingerprint_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let FingerprintArgs { path, expected } = syn::parse2(tokens)?;
+    let path_str = path_string(&path);
+    let mm_path = &roots.macro_magic;
+    Ok(quote! {
+        #mm_path::forward_tokens! {
+            #path,
+            __import_tokens_proc_assert_item_fingerprint_inner,
+            #mm_path,
+            { (#expected, #path_str) }
+        }
+    })
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_assert_item_fingerprint_inner(tokens: TokenStream) -> TokenStream {
+    let ForwardedFingerprint {
+        item,
+        expected,
+        path,
+    } = parse_macro_input!(tokens as ForwardedFingerprint);
+    let actual = fingerprint::fingerprint(&item);
+    if expected.value() == actual {
+        return TokenStream::new();
+    }
+    let message = format!(
+        "`{}` has changed since its fingerprint was recorded\n\
+         - expected: {}\n\
+         + actual:   {}\n\
+         \n\
+         current definition:\n\
+         {}\n\
+         \n\
+         review the change, then update the expected fingerprint to \"{}\"",
+        path.value(),
+        expected.value(),
+        actual,
+        pretty::item(&item),
+        actual,
+    );
+    syn::Error::new(expected.span(), message)
+        .to_compile_error()
+        .into()
+}
+
+/// What the hidden inner macro of [`print_foreign_item`] receives: the item followed by an
+/// `$extra` block holding the path it was imported from as a string literal.
+struct ForwardedItemPath {
+    item: Item,
+    path: LitStr,
+}
+
+impl Parse for ForwardedItemPath {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let path = extra.parse()?;
+        Ok(ForwardedItemPath { item, path })
+    }
+}
+
+/// Adds the `#[export_tokens]` item at the specified path to a JSON lines catalog of imported
+/// items and expands to nothing.
+///
+/// Each line records the importing crate, the export name, the path made absolute and the
+/// tokens of the item, so you can inspect exactly what was exported during a build. The catalog is
+/// written to `$FOREIGN_ITEM_CATALOG` if that is set, and otherwise to
+/// `foreign_item_catalog.jsonl` in `$CARGO_TARGET_DIR` or the `target` directory of the
+/// calling crate. Entries that are already in the catalog are not added again.
+#[proc_macro]
+pub fn print_foreign_item(tokens: TokenStream) -> TokenStream {
+    expand(print_foreign_item_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_print_foreign_item(tokens: TokenStream) -> TokenStream {
+    expand(print_foreign_item_internal(tokens.into(), &Roots::facade()))
+}
+
+fn print_foreign_item_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let path: Path = syn::parse2(tokens)?;
+    let path_str = path_string(&path);
+    let mm_path = &roots.macro_magic;
+    Ok(quote! {
+        #mm_path::forward_tokens! {
+            #path,
+            __import_tokens_proc_print_foreign_item_inner,
+            #mm_path,
+            { #path_str }
+        }
+    })
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_print_foreign_item_inner(tokens: TokenStream) -> TokenStream {
+    let krate = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    expand(print_foreign_item_inner_internal(
+        tokens.into(),
+        &krate,
+        catalog::record,
+    ))
+}
+
+/// Hands the catalog entry for the item to `record`, which [`print_foreign_item`] points at
+/// the catalog itself.
+fn print_foreign_item_inner_internal(
+    tokens: TokenStream2,
+    krate: &str,
+    record: impl FnOnce(&CatalogEntry) -> io::Result<PathBuf>,
+) -> Result<TokenStream2> {
+    let ForwardedItemPath { item, path } = syn::parse2(tokens)?;
+    let path = catalog::resolve_path(&path.value(), krate);
+    let entry = CatalogEntry {
+        krate,
+        export_name: path.rsplit("::").next().unwrap(),
+        path: &path,
+        tokens: &item.to_token_stream().to_string(),
+    };
+    match record(&entry) {
+        Ok(_) => Ok(TokenStream2::new()),
+        Err(err) => Err(syn::Error::new(
+            Span::call_site(),
+            format!(
+                "failed to write `{path}` to {}: {err}",
+                catalog::catalog_path().display()
+            ),
+        )),
+    }
+}
+
+/// Arguments to [`assert_items_equivalent`]: the paths of two `#[export_tokens]` items.
+struct ItemPairArgs {
+    left: Path,
+    right: Path,
+}
+
+impl Parse for ItemPairArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let left = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let right = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(ItemPairArgs { left, right })
+    }
+}
+
+/// Fails compilation unless the two `#[export_tokens]` structs or enums at the specified paths
+/// have the same fields, in the same order and with the same types:
+///
+/// ```ignore
+/// assert_items_equivalent!(foreign_crate::PointOne, foreign_crate::PointTwo);
+/// ```
+///
+/// The error lists every difference as `left vs right`, e.g. `field1: usize vs bool`. The
+/// names, attributes and visibility of the items are not compared.
+#[proc_macro]
+pub fn assert_items_equivalent(tokens: TokenStream) -> TokenStream {
+    expand(assert_items_equivalent_internal(
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_assert_items_equivalent(tokens: TokenStream) -> TokenStream {
+    expand(assert_items_equivalent_internal(
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn assert_items_equivalent_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let ItemPairArgs { left, right } = syn::parse2(tokens)?;
+    let left_str = path_string(&left);
+    let right_str = path_string(&right);
+    Ok(
+        Chain::new(vec![left, right], parse_quote!((#left_str, #right_str)))
+            .forward("__import_tokens_proc_assert_items_equivalent_inner", roots),
+    )
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_assert_items_equivalent_inner(tokens: TokenStream) -> TokenStream {
+    expand(assert_items_equivalent_inner_internal(
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_proc_assert_items_equivalent_inner(
+    tokens: TokenStream,
+) -> TokenStream {
+    expand(assert_items_equivalent_inner_internal(
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn assert_items_equivalent_inner_internal(
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let ForwardedChain(chain) = syn::parse2(tokens)?;
+    if !chain.remaining.is_empty() {
+        return Ok(chain.forward("__import_tokens_proc_assert_items_equivalent_inner", roots));
+    }
+    let [left, right] = &chain.collected[..] else {
+        unreachable!("two paths are imported");
+    };
+    let differences = compare::compare_items(left, right);
+    if differences.is_empty() {
+        return Ok(TokenStream2::new());
+    }
+    let paths: Vec<String> = match &chain.payload {
+        Expr::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .filter_map(|elem| match elem {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(path),
+                    ..
+                }) => Some(path.value()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let [left_path, right_path] = &paths[..] else {
+        unreachable!("the payload holds the two paths");
+    };
+    let mut message = format!("`{left_path}` and `{right_path}` are not equivalent:");
+    for difference in differences {
+        message.push_str("\n  ");
+        message.push_str(&difference);
+    }
+    Err(syn::Error::new(Span::call_site(), message))
+}
+
+/// Arguments to [`mirror_item`]: optional outer attributes, the path of an `#[export_tokens]`
+/// item and an optional `as Name`.
+struct MirrorArgs {
+    attrs: Vec<Attribute>,
+    path: Path,
+    name: Option<Ident>,
+}
+
+impl Parse for MirrorArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let NamedItemArgs { path, name, .. } = input.parse()?;
+        Ok(MirrorArgs { attrs, path, name })
+    }
+}
+
+/// Re-declares the `#[export_tokens]` struct or enum at the specified path in the calling
+/// crate as a `pub` item with `pub` fields, regardless of its visibility in the crate it was
+/// exported from:
+///
+/// ```ignore
+/// mirror_item!(#[derive(Debug, Clone, PartialEq)] foreign_crate::StructOne as LocalOne);
+/// ```
+///
+/// Attributes given before the path are attached to the new item in place of the original
+/// ones, of which only doc comments and `#[repr(..)]` are kept. Without `as Name` the new item
+/// is named after the export name of the original, i.e. `StructOne` above. Generics, field
+/// types and variants are copied as they are, so any types they mention must be in scope at
+/// the call site.
+#[proc_macro]
+pub fn mirror_item(tokens: TokenStream) -> TokenStream {
+    expand(mirror_item_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_mirror_item(tokens: TokenStream) -> TokenStream {
+    expand(mirror_item_internal(tokens.into(), &Roots::facade()))
+}
+
+fn mirror_item_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let MirrorArgs { attrs, path, name } = syn::parse2(tokens)?;
+    let name = name.unwrap_or_else(|| path.segments.last().unwrap().ident.clone());
+    let mm_path = &roots.macro_magic;
+    Ok(quote! {
+        #mm_path::forward_tokens! {
+            #path,
+            __import_tokens_proc_mirror_item_inner,
+            #mm_path,
+            { #(#attrs)* struct #name; }
+        }
+    })
+}
+
+/// What the hidden inner macro of [`mirror_item`] receives: the item followed by an `$extra`
+/// block holding a unit struct with the attributes and name the mirrored item should get.
+struct ForwardedMirror {
+    item: Item,
+    template: ItemStruct,
+}
+
+impl Parse for ForwardedMirror {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let template = extra.parse()?;
+        Ok(ForwardedMirror { item, template })
+    }
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_mirror_item_inner(tokens: TokenStream) -> TokenStream {
+    let ForwardedMirror { item, template } = parse_macro_input!(tokens as ForwardedMirror);
+    let keep_attr = |attr: &Attribute| attr.path().is_ident("doc") || attr.path().is_ident("repr");
+    let public: Visibility = parse_quote!(pub);
+    let mirrored = match item {
+        Item::Struct(mut item) => {
+            item.attrs.retain(keep_attr);
+            item.attrs.extend(template.attrs);
+            item.vis = public.clone();
+            item.ident = template.ident;
+            for field in item.fields.iter_mut() {
+                field.vis = public.clone();
+            }
+            Item::Struct(item)
+        }
+        Item::Enum(mut item) => {
+            item.attrs.retain(keep_attr);
+            item.attrs.extend(template.attrs);
+            item.vis = public;
+            item.ident = template.ident;
+            Item::Enum(item)
+        }
+        item => {
+            return syn::Error::new(
+                template.ident.span(),
+                format!(
+                    "mirror_item! only supports structs and enums, not {}s",
+                    compare::kind(&item)
+                ),
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    mirrored.to_token_stream().into()
+}
+
+/// Emits a `const &'static [&'static str]` with the names of the fields of the
+/// `#[export_tokens]` struct at the specified path, in declaration order. Tuple fields are
+/// named after their index (`"0"`, `"1"`, ...) and unit structs have no fields.
+///
+/// For an enum, name the variant whose fields you want after the path:
+///
+/// ```ignore
+/// field_names!(foreign_crate::StructOne);
+/// field_names!(foreign_crate::Shape, Circle as CIRCLE_FIELDS);
+/// ```
+///
+/// The constant is named after the export name of the item and the variant, if any, i.e.
+/// `STRUCT_ONE_FIELD_NAMES` or `SHAPE_CIRCLE_FIELD_NAMES`, unless a name is given with
+/// `as NAME`.
+#[proc_macro]
+pub fn field_names(tokens: TokenStream) -> TokenStream {
+    expand(field_names_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_field_names(tokens: TokenStream) -> TokenStream {
+    expand(field_names_internal(tokens.into(), &Roots::facade()))
+}
+
+fn field_names_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args: FieldListArgs = syn::parse2(tokens)?;
+    Ok(args.forward(
+        "__import_tokens_proc_field_names_inner",
+        "FIELD_NAMES",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_field_names_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedFieldList);
+    let fields = match forwarded.fields() {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let names = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| compare::field_name(field, i));
+    let name = &forwarded.name;
     quote! {
-        const ITEM_SRC: &'static str = #item_str;
+        const #name: &'static [&'static str] = &[#(#names),*];
     }
     .into()
 }
 
-#[import_tokens_proc]
+/// Like [`field_names`], but lists the types of the fields, formatted the way `rustfmt` would
+/// write them. The default constant name ends in `FIELD_TYPES`.
+///
+/// ```ignore
+/// field_types!(foreign_crate::StructOne);
+///
+/// assert_eq!(STRUCT_ONE_FIELD_TYPES, &["usize"]);
+/// ```
 #[proc_macro]
-pub fn print_foreign_item(tokens: TokenStream) -> TokenStream {
-    println!("{}", tokens.to_string());
-    "".parse().unwrap()
+pub fn field_types(tokens: TokenStream) -> TokenStream {
+    expand(field_types_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_field_types(tokens: TokenStream) -> TokenStream {
+    expand(field_types_internal(tokens.into(), &Roots::facade()))
+}
+
+fn field_types_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args: FieldListArgs = syn::parse2(tokens)?;
+    Ok(args.forward(
+        "__import_tokens_proc_field_types_inner",
+        "FIELD_TYPES",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_field_types_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedFieldList);
+    let fields = match forwarded.fields() {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let types = fields.iter().map(|field| pretty::ty(&field.ty));
+    let name = &forwarded.name;
+    quote! {
+        const #name: &'static [&'static str] = &[#(#types),*];
+    }
+    .into()
+}
+
+/// Emits a `const &'static str` holding a JSON Schema (draft 2020-12) for the
+/// `#[export_tokens]` struct or enum at the specified path, describing the JSON `serde` would
+/// produce for it with its default settings.
+///
+/// Structs become objects whose fields are required unless they are `Option`s, tuple structs
+/// become fixed-length arrays and enums become a `oneOf` of their externally tagged variants.
+/// Primitives, strings and the std collections map to the matching schema types, anything else
+/// becomes a `$ref` to `#/$defs/` followed by the type as written, i.e. `#/$defs/Point`, and
+/// percent-encoded where the type contains characters like `<` or spaces. Every referenced type
+/// gets an empty schema under `$defs`, keyed by the type as written, which accepts any value, so
+/// the document is valid as it is and the definitions can be filled in where the types are
+/// known.
+///
+/// ```ignore
+/// json_schema!(foreign_crate::StructTwo);
+///
+/// assert!(STRUCT_TWO_SCHEMA.contains(r#""field1":{"type":"boolean"}"#));
+/// ```
+///
+/// The constant is named after the export name of the item, unless a name is given with
+/// `as NAME`.
+#[proc_macro]
+pub fn json_schema(tokens: TokenStream) -> TokenStream {
+    expand(json_schema_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_json_schema(tokens: TokenStream) -> TokenStream {
+    expand(json_schema_internal(tokens.into(), &Roots::facade()))
+}
+
+fn json_schema_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_json_schema_inner",
+        "SCHEMA",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_json_schema_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedNamedItem);
+    forwarded
+        .emit(|item, name| {
+            let schema = match schema::item_schema(item, name.span()) {
+                Ok(schema) => schema.to_string(),
+                Err(err) => return err.to_compile_error(),
+            };
+            quote! {
+                const #name: &'static str = #schema;
+            }
+        })
+        .into()
+}
+
+/// Expands to a `forward_tokens!` call that hands the item at `path` to `inner_macro`, passing
+/// the item the attribute is attached to along as the `$extra` block.
+fn forward_attached(
+    path: Path,
+    attached: impl ToTokens,
+    inner_macro: &str,
+    roots: &Roots,
+) -> TokenStream2 {
+    let inner_macro = Ident::new(inner_macro, Span::call_site());
+    let mm_path = &roots.macro_magic;
+    quote! {
+        #mm_path::forward_tokens! {
+            #path,
+            #inner_macro,
+            #mm_path,
+            { #attached }
+        }
+    }
+}
+
+/// What the hidden inner macros of [`copy_fields_from`], [`mock_of`] and [`assert_same_shape`]
+/// receive: the imported item followed by an `$extra` block holding the item the attribute is
+/// attached to.
+struct ForwardedAttached<T> {
+    imported: Item,
+    attached: T,
+}
+
+impl<T: Parse> Parse for ForwardedAttached<T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let imported = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra;
+        braced!(extra in input);
+        let attached = extra.parse()?;
+        Ok(ForwardedAttached { imported, attached })
+    }
+}
+
+/// The named fields of a struct, or none for a unit struct. Tuple structs are reported at `span`.
+fn named_fields(item: &ItemStruct, span: Span) -> Result<Vec<Field>> {
+    match &item.fields {
+        Fields::Named(fields) => Ok(fields.named.iter().cloned().collect()),
+        Fields::Unit => Ok(Vec::new()),
+        Fields::Unnamed(_) => Err(syn::Error::new(
+            span,
+            format!(
+                "copy_fields_from only supports structs with named fields, `{}` is a tuple struct",
+                item.ident
+            ),
+        )),
+    }
+}
+
+/// Merges the fields of the `#[export_tokens]` struct at the specified path into the struct the
+/// attribute is attached to. The imported fields come first, in their original order, followed
+/// by the local ones:
+///
+/// ```ignore
+/// #[copy_fields_from(foreign_crate::StructOne)]
+/// struct Local {
+///     extra: u8,
+/// }
+///
+/// let local = Local { field1: 3, extra: 4 };
+/// ```
+///
+/// A local field with the same name as an imported one replaces it in place, so it can be used
+/// to change the type, visibility or attributes of a single imported field. Both structs must
+/// have named fields (or none at all).
+///
+/// Like [`delegate_trait`], this calls `forward_tokens!` itself rather than going through
+/// `#[import_tokens_attr]`, and its inner macro keeps the name that would have given it.
+#[proc_macro_attribute]
+pub fn copy_fields_from(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(copy_fields_from_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn __facade_copy_fields_from(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(copy_fields_from_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn copy_fields_from_internal(
+    attr: TokenStream2,
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let path: Path = syn::parse2(attr)?;
+    let local: ItemStruct = syn::parse2(tokens)?;
+    Ok(forward_attached(
+        path,
+        local,
+        "__import_tokens_attr_copy_fields_from_inner",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_attr_copy_fields_from_inner(tokens: TokenStream) -> TokenStream {
+    expand(copy_fields_from_inner_internal(tokens.into()))
+}
+
+fn copy_fields_from_inner_internal(tokens: TokenStream2) -> Result<TokenStream2> {
+    let ForwardedAttached {
+        imported,
+        attached: mut local,
+    } = syn::parse2::<ForwardedAttached<ItemStruct>>(tokens)?;
+    let imported = match imported {
+        Item::Struct(imported) => imported,
+        item => {
+            return Err(syn::Error::new(
+                local.ident.span(),
+                format!(
+                    "copy_fields_from only copies the fields of structs, not {}s",
+                    compare::kind(&item)
+                ),
+            ))
+        }
+    };
+    let imported_fields = named_fields(&imported, local.ident.span())?;
+    let local_fields = named_fields(&local, local.ident.span())?;
+    let mut fields = imported_fields;
+    for field in local_fields {
+        match fields.iter_mut().find(|f| f.ident == field.ident) {
+            Some(imported_field) => *imported_field = field,
+            None => fields.push(field),
+        }
+    }
+    if fields.is_empty() {
+        local.fields = Fields::Unit;
+        local.semi_token = Some(Default::default());
+    } else {
+        local.fields = Fields::Named(parse_quote!({ #(#fields),* }));
+        local.semi_token = None;
+    }
+    Ok(local.to_token_stream())
+}
+
+/// Implements the `#[export_tokens]` trait at the specified path for the struct the attribute
+/// is attached to by forwarding every associated const, type and method to one of its fields:
+///
+/// ```ignore
+/// #[delegate_trait(foreign_crate::Describe, to = inner)]
+/// struct Wrapper {
+///     inner: Verbose,
+/// }
+/// ```
+///
+/// expands to the struct followed by
+///
+/// ```ignore
+/// impl foreign_crate::Describe for Wrapper
+/// where
+///     Verbose: foreign_crate::Describe,
+/// {
+///     const NAME: &'static str = <Verbose as foreign_crate::Describe>::NAME;
+///     type Output = <Verbose as foreign_crate::Describe>::Output;
+///     fn describe(&self, verbose: bool) -> Self::Output {
+///         <Verbose as foreign_crate::Describe>::describe(&self.inner, verbose)
+///     }
+/// }
+/// ```
+///
+/// The impl is generated from the imported trait tokens, so it picks up new methods as soon as
+/// the trait gains them. That also means the path is used to name the trait in the impl, so it
+/// must point at the trait itself as well as at its export, i.e. the trait has to be
+/// re-exported under its export name. Tuple structs use the index of the field, `to = 0`.
+///
+/// `#[import_tokens_attr]` only accepts a path, so like [`make_item_const`] this calls
+/// `forward_tokens!` itself. The inner macro keeps the name `#[import_tokens_attr]` would have
+/// given it so that `#[macro_magic::use_attr]` still imports it.
+#[proc_macro_attribute]
+pub fn delegate_trait(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(delegate_trait_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn __facade_delegate_trait(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(delegate_trait_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn delegate_trait_internal(
+    attr: TokenStream2,
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let args: DelegateArgs = syn::parse2(attr)?;
+    let wrapper: ItemStruct = syn::parse2(tokens)?;
+    Ok(args.forward(wrapper, "__import_tokens_attr_delegate_trait_inner", roots))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_attr_delegate_trait_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedDelegate);
+    match forwarded.delegate() {
+        Ok(tokens) => tokens.into(),
+        Err(err) => {
+            let wrapper = &forwarded.wrapper;
+            let err = err.to_compile_error();
+            quote!(#wrapper #err).into()
+        }
+    }
+}
+
+/// Generates a mock of the `#[export_tokens]` trait at the specified path. Attach it to an impl
+/// of the trait for the mock, which supplies the associated consts and types as well as any
+/// methods you'd rather write yourself:
+///
+/// ```ignore
+/// #[mock_of(foreign_crate::Describe)]
+/// impl foreign_crate::Describe for MockDescribe {
+///     const NAME: &'static str = "mock";
+///     type Output = String;
+/// }
+///
+/// let mock = MockDescribe::new();
+/// mock.queue_describe("first".to_string());
+/// assert_eq!(mock.describe(true), "first");
+/// assert_eq!(mock.calls_to("describe")[0].args, ["true"]);
+/// ```
+///
+/// This declares `pub struct MockDescribe` and fills in every other method of the trait. Each
+/// one records a `types_crate::MockCall` with its arguments formatted
+/// with `Debug`, then returns the next value queued with `queue_<method>`, panicking if there is
+/// none. Methods that return `()` don't need queued values. `calls()` and `calls_to(method)`
+/// list the calls made so far.
+///
+/// Methods without a `self` receiver, and methods whose return type depends on their own
+/// generic parameters, can't be mocked and have to be written in the impl block.
+///
+/// Like [`copy_fields_from`], this calls `forward_tokens!` itself.
+#[proc_macro_attribute]
+pub fn mock_of(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(mock_of_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn __facade_mock_of(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(mock_of_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn mock_of_internal(
+    attr: TokenStream2,
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let path: Path = syn::parse2(attr)?;
+    let skeleton: ItemImpl = syn::parse2(tokens)?;
+    Ok(forward_attached(
+        path,
+        skeleton,
+        "__import_tokens_attr_mock_of_inner",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_attr_mock_of_inner(tokens: TokenStream) -> TokenStream {
+    expand(mock_of_inner_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_attr_mock_of_inner(tokens: TokenStream) -> TokenStream {
+    expand(mock_of_inner_internal(tokens.into(), &Roots::facade()))
+}
+
+fn mock_of_inner_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let ForwardedAttached { imported, attached } = syn::parse2(tokens)?;
+    mock::mock(&imported, attached, &roots.types_crate)
+}
+
+/// Fails compilation unless the struct it is attached to declares the same fields as the
+/// `#[export_tokens]` struct at the specified path, with the same names, in the same order and
+/// with the same types. Each difference is reported on the offending local field:
+///
+/// ```ignore
+/// #[assert_same_shape(foreign_crate::StructTwo)]
+/// pub struct WireTwo {
+///     pub field1: u8,
+/// }
+/// ```
+///
+/// fails with "`field1` is a `bool` in `MyStruct`, not a `u8`" pointing at `u8`. Types are
+/// compared as written, so `Vec<u8>` and `std::vec::Vec<u8>` count as different types. The
+/// attached struct is emitted unchanged, along with any errors.
+///
+/// Like [`copy_fields_from`], this calls `forward_tokens!` itself.
+#[proc_macro_attribute]
+pub fn assert_same_shape(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(assert_same_shape_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn __facade_assert_same_shape(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(assert_same_shape_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn assert_same_shape_internal(
+    attr: TokenStream2,
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let path: Path = syn::parse2(attr)?;
+    let local: ItemStruct = syn::parse2(tokens)?;
+    Ok(forward_attached(
+        path,
+        local,
+        "__import_tokens_attr_assert_same_shape_inner",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_attr_assert_same_shape_inner(tokens: TokenStream) -> TokenStream {
+    let ForwardedAttached {
+        imported,
+        attached: local,
+    } = parse_macro_input!(tokens as ForwardedAttached<ItemStruct>);
+    let errors = compare::shape_errors(&local, &imported)
+        .into_iter()
+        .map(|err| err.to_compile_error());
+    quote! {
+        #local
+        #(#errors)*
+    }
+    .into()
+}
+
+/// Copies the variants of the `#[export_tokens]` enum at the specified path into the enum the
+/// attribute is attached to, ahead of its own variants. If the local enum has no generics it
+/// takes those of the upstream enum, otherwise it has to declare them all and can add its own.
+/// The `From` impl below converts the upstream enum with its own parameters, i.e.
+/// `Reply<T>` into `LocalReply<T, E>`.
+///
+/// Whether the upstream enum can be named from the calling crate is something a macro can't
+/// find out, so you tell it: pass `from` if the path also names the enum itself, or
+/// `from = path` if it is reachable elsewhere, and a `From` impl converting the upstream enum
+/// is generated:
+///
+/// ```ignore
+/// #[extend_enum(foreign_crate::ErrorKind, from)]
+/// pub enum LocalErrorKind {
+///     Timeout,
+/// }
+///
+/// assert_eq!(LocalErrorKind::from(ErrorKind::NotFound), LocalErrorKind::NotFound);
+/// ```
+///
+/// Without `from` the enum gets an `UPSTREAM_VARIANTS` constant listing the names of the copied
+/// variants instead. Local variants that share a name with an upstream one are errors.
+#[proc_macro_attribute]
+pub fn extend_enum(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(extend_enum_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn __facade_extend_enum(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    expand(extend_enum_internal(
+        attr.into(),
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn extend_enum_internal(
+    attr: TokenStream2,
+    tokens: TokenStream2,
+    roots: &Roots,
+) -> Result<TokenStream2> {
+    let args: ExtendArgs = syn::parse2(attr)?;
+    let local: ItemEnum = syn::parse2(tokens)?;
+    Ok(args.forward(local, "__import_tokens_attr_extend_enum_inner", roots))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_attr_extend_enum_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedExtend);
+    let local = forwarded.local.clone();
+    match forwarded.extend() {
+        Ok(tokens) => tokens.into(),
+        Err(err) => {
+            let err = err.to_compile_error();
+            quote!(#local #err).into()
+        }
+    }
+}
+
+/// Emits a `const &'static [(&'static str, types_crate::ItemKind)]` listing the ident and kind
+/// of every item in the `#[export_tokens]` module at the specified path, in order:
+///
+/// ```ignore
+/// list_module_items!(foreign_crate::FirstMod);
+///
+/// assert_eq!(FIRST_MOD_ITEMS[0], ("MyStruct", ItemKind::Struct));
+/// ```
+///
+/// Impl blocks are listed under the name of the trait they implement, if any, and `use` items
+/// under `""`. The constant is named after the export name of the module, unless a name is
+/// given with `as NAME`.
+#[proc_macro]
+pub fn list_module_items(tokens: TokenStream) -> TokenStream {
+    expand(list_module_items_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_list_module_items(tokens: TokenStream) -> TokenStream {
+    expand(list_module_items_internal(tokens.into(), &Roots::facade()))
+}
+
+fn list_module_items_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args = NamedItemArgs::parse_with_cfg.parse2(tokens)?;
+    Ok(forward_named_item(
+        args,
+        "__import_tokens_proc_list_module_items_inner",
+        "ITEMS",
+        roots,
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_list_module_items_inner(tokens: TokenStream) -> TokenStream {
+    expand(list_module_items_inner_internal(
+        tokens.into(),
+        &Roots::direct(),
+    ))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_import_tokens_proc_list_module_items_inner(tokens: TokenStream) -> TokenStream {
+    expand(list_module_items_inner_internal(
+        tokens.into(),
+        &Roots::facade(),
+    ))
+}
+
+fn list_module_items_inner_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let forwarded: ForwardedNamedItem = syn::parse2(tokens)?;
+    let types_crate = &roots.types_crate;
+    Ok(forwarded.emit(|item, name| {
+        let module = match expect_module(item, name) {
+            Ok(module) => module,
+            Err(err) => return err.to_compile_error(),
+        };
+        let entries = module_items(module)
+            .iter()
+            .map(meta::item_meta)
+            .map(|meta| {
+                let ident = meta.ident();
+                let kind = meta.kind(types_crate);
+                quote!((#ident, #kind))
+            });
+        quote! {
+            const #name: &'static [(&'static str, #types_crate::ItemKind)] = &[#(#entries),*];
+        }
+    }))
+}
+
+/// The items of a module, of which there are none if it isn't inline.
+fn module_items(module: &ItemMod) -> &[Item] {
+    match &module.content {
+        Some((_, items)) => items,
+        None => &[],
+    }
+}
+
+/// Reports anything but a module at `name`.
+fn expect_module<'a>(item: &'a Item, name: &Ident) -> Result<&'a ItemMod> {
+    match item {
+        Item::Mod(module) => Ok(module),
+        item => Err(syn::Error::new(
+            name.span(),
+            format!("expected a module, not a {}", compare::kind(item)),
+        )),
+    }
+}
+
+/// Like [`make_item_const`], but for a single item of the `#[export_tokens]` module at the
+/// specified path, named after the path:
+///
+/// ```ignore
+/// module_item_src!(foreign_crate::FirstMod, MyStruct);
+/// module_item_src!(foreign_crate::FirstMod, Point as POINT_SRC);
+/// ```
+///
+/// The first constant is named `FIRST_MOD_MY_STRUCT_SRC`. Items are looked up by the ident
+/// [`list_module_items`] lists them under, so impl blocks are found by the name of the trait
+/// they implement. Attributes of the item, including any `#[export_tokens]` of its own, are
+/// kept.
+#[proc_macro]
+pub fn module_item_src(tokens: TokenStream) -> TokenStream {
+    expand(module_item_src_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_module_item_src(tokens: TokenStream) -> TokenStream {
+    expand(module_item_src_internal(tokens.into(), &Roots::facade()))
+}
+
+fn module_item_src_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args: FieldListArgs = syn::parse2(tokens)?;
+    Ok(args.forward("__import_tokens_proc_module_item_src_inner", "SRC", roots))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_module_item_src_inner(tokens: TokenStream) -> TokenStream {
+    let ForwardedFieldList { item, name, member } =
+        parse_macro_input!(tokens as ForwardedFieldList);
+    let module = match expect_module(&item, &name) {
+        Ok(module) => module,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let Some(wanted) = member else {
+        return syn::Error::new(
+            name.span(),
+            "name the item you want after the path, e.g. `FirstMod, MyStruct`",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let Some(found) = module_items(module)
+        .iter()
+        .find(|item| wanted == meta::item_meta(item).ident())
+    else {
+        return syn::Error::new(
+            wanted.span(),
+            format!("`{}` has no item named `{wanted}`", module.ident),
+        )
+        .to_compile_error()
+        .into();
+    };
+    let item_str = found.to_token_stream().to_string();
+    quote! {
+        const #name: &'static str = #item_str;
+    }
+    .into()
+}
+
+/// Re-emits the `#[export_tokens]` fn at the specified path in the calling crate under a new
+/// name, private or not in the crate it was exported from:
+///
+/// ```ignore
+/// inline_foreign_fn!(foreign_crate::classify as local_classify);
+/// inline_foreign_fn!(#[inline] pub foreign_crate::digits as count_digits);
+/// ```
+///
+/// Attributes and a visibility given before the path are applied to the new fn, which is
+/// private otherwise. Its body is copied as it is, except that references to the fn itself,
+/// like recursive calls or `.map(digits)`, use the new name, and `crate::` paths are rewritten
+/// to start at the root of the foreign crate, i.e. `::foreign_crate::`. Those paths have to be
+/// visible from the calling crate. `self::` and `super::` paths can't be rewritten, since the
+/// module they are relative to isn't known, and are reported. Anything the body refers to
+/// without a path must be in scope at the call site.
+#[proc_macro]
+pub fn inline_foreign_fn(tokens: TokenStream) -> TokenStream {
+    expand(inline_foreign_fn_internal(tokens.into(), &Roots::direct()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __facade_inline_foreign_fn(tokens: TokenStream) -> TokenStream {
+    expand(inline_foreign_fn_internal(tokens.into(), &Roots::facade()))
+}
+
+fn inline_foreign_fn_internal(tokens: TokenStream2, roots: &Roots) -> Result<TokenStream2> {
+    let args: InlineArgs = syn::parse2(tokens)?;
+    Ok(args.forward("__import_tokens_proc_inline_foreign_fn_inner", roots))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __import_tokens_proc_inline_foreign_fn_inner(tokens: TokenStream) -> TokenStream {
+    let forwarded = parse_macro_input!(tokens as ForwardedInline);
+    match forwarded.inline() {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
@@ -0,0 +1,255 @@
+//! Builds the `types_crate::ItemMeta` expressions emitted by `item_meta!`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    Attribute, Fields, FnArg, Generics, Ident, ImplItem, Item, Path, ReturnType, Signature,
+    TraitItem, Type, Visibility,
+};
+
+use crate::pretty;
+
+/// Compile-time mirror of `types_crate::ItemMeta`, whose [`Meta::expr`] is the equivalent
+/// constant expression.
+#[derive(Default)]
+pub struct Meta {
+    kind: &'static str,
+    ident: String,
+    generics: String,
+    visibility: String,
+    attributes: Vec<String>,
+    fields: Vec<FieldMeta>,
+    variants: Vec<VariantMeta>,
+    ty: Option<String>,
+    items: Vec<Meta>,
+}
+
+/// Compile-time mirror of `types_crate::FieldMeta`.
+pub struct FieldMeta {
+    name: String,
+    ty: String,
+}
+
+/// Compile-time mirror of `types_crate::VariantMeta`.
+pub struct VariantMeta {
+    name: String,
+    fields: Vec<FieldMeta>,
+}
+
+impl Meta {
+    fn new(kind: &'static str, attrs: &[Attribute], vis: &Visibility) -> Self {
+        Meta {
+            kind,
+            attributes: attributes(attrs),
+            visibility: pretty::visibility(vis),
+            ..Default::default()
+        }
+    }
+
+    fn named(mut self, ident: &Ident, generics: &Generics) -> Self {
+        self.ident = ident.to_string();
+        self.generics = pretty::generics(generics);
+        self
+    }
+
+    fn with_ty(mut self, ty: &Type) -> Self {
+        self.ty = Some(pretty::ty(ty));
+        self
+    }
+
+    /// The ident of the item, or the name of the implemented trait for impl blocks. Empty for
+    /// inherent impls and `use` items.
+    pub fn ident(&self) -> &str {
+        &self.ident
+    }
+
+    /// The `types_crate::ItemKind` of the item, with `types_crate` at the path given.
+    pub fn kind(&self, types_crate: &Path) -> TokenStream2 {
+        let kind = Ident::new(self.kind, Span::call_site());
+        quote!(#types_crate::ItemKind::#kind)
+    }
+
+    fn with_sig(mut self, sig: &Signature) -> Self {
+        self = self.named(&sig.ident, &sig.generics);
+        self.fields = sig.inputs.iter().map(param).collect();
+        if let ReturnType::Type(_, ty) = &sig.output {
+            self.ty = Some(pretty::ty(ty));
+        }
+        self
+    }
+}
+
+/// Describes an arbitrary item.
+pub fn item_meta(item: &Item) -> Meta {
+    match item {
+        Item::Struct(item) => Meta {
+            fields: fields(&item.fields),
+            ..Meta::new("Struct", &item.attrs, &item.vis).named(&item.ident, &item.generics)
+        },
+        Item::Enum(item) => Meta {
+            variants: item
+                .variants
+                .iter()
+                .map(|variant| VariantMeta {
+                    name: variant.ident.to_string(),
+                    fields: fields(&variant.fields),
+                })
+                .collect(),
+            ..Meta::new("Enum", &item.attrs, &item.vis).named(&item.ident, &item.generics)
+        },
+        Item::Union(item) => Meta {
+            fields: fields(&Fields::Named(item.fields.clone())),
+            ..Meta::new("Union", &item.attrs, &item.vis).named(&item.ident, &item.generics)
+        },
+        Item::Trait(item) => Meta {
+            items: item.items.iter().map(trait_item_meta).collect(),
+            ..Meta::new("Trait", &item.attrs, &item.vis).named(&item.ident, &item.generics)
+        },
+        Item::Fn(item) => Meta::new("Fn", &item.attrs, &item.vis).with_sig(&item.sig),
+        Item::Const(item) => Meta::new("Const", &item.attrs, &item.vis)
+            .named(&item.ident, &item.generics)
+            .with_ty(&item.ty),
+        Item::Static(item) => Meta::new("Static", &item.attrs, &item.vis)
+            .named(&item.ident, &Generics::default())
+            .with_ty(&item.ty),
+        Item::Type(item) => Meta::new("Type", &item.attrs, &item.vis)
+            .named(&item.ident, &item.generics)
+            .with_ty(&item.ty),
+        Item::Mod(item) => Meta {
+            items: match &item.content {
+                Some((_, items)) => items.iter().map(item_meta).collect(),
+                None => Vec::new(),
+            },
+            ..Meta::new("Mod", &item.attrs, &item.vis).named(&item.ident, &Generics::default())
+        },
+        Item::Impl(item) => Meta {
+            ident: match &item.trait_ {
+                Some((_, path, _)) => path.segments.last().unwrap().ident.to_string(),
+                None => String::new(),
+            },
+            generics: pretty::generics(&item.generics),
+            items: item.items.iter().map(impl_item_meta).collect(),
+            ..Meta::new("Impl", &item.attrs, &Visibility::Inherited).with_ty(&item.self_ty)
+        },
+        Item::Use(item) => Meta::new("Use", &item.attrs, &item.vis),
+        _ => Meta::new("Other", &[], &Visibility::Inherited),
+    }
+}
+
+fn trait_item_meta(item: &TraitItem) -> Meta {
+    let vis = Visibility::Inherited;
+    match item {
+        TraitItem::Fn(item) => Meta::new("Fn", &item.attrs, &vis).with_sig(&item.sig),
+        TraitItem::Const(item) => Meta::new("Const", &item.attrs, &vis)
+            .named(&item.ident, &item.generics)
+            .with_ty(&item.ty),
+        TraitItem::Type(item) => Meta {
+            ty: item.default.as_ref().map(|(_, ty)| pretty::ty(ty)),
+            ..Meta::new("Type", &item.attrs, &vis).named(&item.ident, &item.generics)
+        },
+        _ => Meta::new("Other", &[], &vis),
+    }
+}
+
+fn impl_item_meta(item: &ImplItem) -> Meta {
+    match item {
+        ImplItem::Fn(item) => Meta::new("Fn", &item.attrs, &item.vis).with_sig(&item.sig),
+        ImplItem::Const(item) => Meta::new("Const", &item.attrs, &item.vis)
+            .named(&item.ident, &item.generics)
+            .with_ty(&item.ty),
+        ImplItem::Type(item) => Meta::new("Type", &item.attrs, &item.vis)
+            .named(&item.ident, &item.generics)
+            .with_ty(&item.ty),
+        _ => Meta::new("Other", &[], &Visibility::Inherited),
+    }
+}
+
+/// Renders every attribute except doc comments.
+fn attributes(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("doc"))
+        .map(pretty::attribute)
+        .collect()
+}
+
+fn fields(fields: &Fields) -> Vec<FieldMeta> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| FieldMeta {
+            name: match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            },
+            ty: pretty::ty(&field.ty),
+        })
+        .collect()
+}
+
+fn param(arg: &FnArg) -> FieldMeta {
+    match arg {
+        FnArg::Receiver(receiver) => FieldMeta {
+            name: "self".to_string(),
+            ty: pretty::ty(&receiver.ty),
+        },
+        FnArg::Typed(arg) => FieldMeta {
+            name: pretty::pat(&arg.pat),
+            ty: pretty::ty(&arg.ty),
+        },
+    }
+}
+
+impl Meta {
+    /// The `types_crate::ItemMeta` expression, with `types_crate` at the path given.
+    pub fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let kind = self.kind(types_crate);
+        let ident = &self.ident;
+        let generics = &self.generics;
+        let visibility = &self.visibility;
+        let attributes = &self.attributes;
+        let fields = self.fields.iter().map(|field| field.expr(types_crate));
+        let variants = self
+            .variants
+            .iter()
+            .map(|variant| variant.expr(types_crate));
+        let ty = match &self.ty {
+            Some(ty) => quote!(::core::option::Option::Some(#ty)),
+            None => quote!(::core::option::Option::None),
+        };
+        let items = self.items.iter().map(|item| item.expr(types_crate));
+        quote! {
+            #types_crate::ItemMeta {
+                kind: #kind,
+                ident: #ident,
+                generics: #generics,
+                visibility: #visibility,
+                attributes: &[#(#attributes),*],
+                fields: &[#(#fields),*],
+                variants: &[#(#variants),*],
+                ty: #ty,
+                items: &[#(#items),*],
+            }
+        }
+    }
+}
+
+impl FieldMeta {
+    fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let name = &self.name;
+        let ty = &self.ty;
+        quote! {
+            #types_crate::FieldMeta { name: #name, ty: #ty }
+        }
+    }
+}
+
+impl VariantMeta {
+    fn expr(&self, types_crate: &Path) -> TokenStream2 {
+        let name = &self.name;
+        let fields = self.fields.iter().map(|field| field.expr(types_crate));
+        quote! {
+            #types_crate::VariantMeta { name: #name, fields: &[#(#fields),*] }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Generates mock implementations of imported traits for `#[mock_of]`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote,
+    visit_mut::{self, VisitMut},
+    Error, FnArg, GenericParam, Ident, ImplItem, Item, ItemImpl, Lifetime, Path, Result,
+    ReturnType, TraitItem, Type, TypeReference,
+};
+
+use crate::{compare, delegate::forwarded_signature};
+
+/// Completes `skeleton`, an impl of the imported trait for the mock, with a method for every
+/// trait method it doesn't implement itself, and declares the mock struct those methods record
+/// their calls in and take their return values from. The calls are recorded as
+/// `types_crate::MockCall`s, with `types_crate` at the path given.
+pub fn mock(imported: &Item, mut skeleton: ItemImpl, types_crate: &Path) -> Result<TokenStream2> {
+    let mock = mock_ident(&skeleton)?;
+    let trait_item = match imported {
+        Item::Trait(item) => item,
+        item => {
+            return Err(Error::new(
+                mock.span(),
+                format!("mock_of expects a trait, not a {}", compare::kind(item)),
+            ))
+        }
+    };
+    let mut concrete = Concrete {
+        mock: mock.clone(),
+        assoc_types: skeleton
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Type(item) => Some((item.ident.clone(), item.ty.clone())),
+                _ => None,
+            })
+            .collect(),
+    };
+    let mut queues = Vec::new();
+    let mut queue_fns = Vec::new();
+    let mut methods = Vec::new();
+    for item in &trait_item.items {
+        let TraitItem::Fn(item) = item else {
+            continue;
+        };
+        let implemented = skeleton.items.iter().any(|impl_item| match impl_item {
+            ImplItem::Fn(impl_item) => impl_item.sig.ident == item.sig.ident,
+            _ => false,
+        });
+        if implemented {
+            continue;
+        }
+        let method = &item.sig.ident;
+        if item.sig.receiver().is_none() {
+            return Err(Error::new(
+                mock.span(),
+                format!(
+                    "`{method}` has no `self` to record calls in, implement it in the impl block \
+                     yourself"
+                ),
+            ));
+        }
+        let type_params: Vec<Ident> = item
+            .sig
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(param) => Some(param.ident.clone()),
+                _ => None,
+            })
+            .collect();
+        let (sig, args) = forwarded_signature(&item.sig);
+        let recorded_args = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(input) => Some(input),
+                FnArg::Receiver(_) => None,
+            })
+            .zip(&args)
+            .map(
+                |(input, arg)| match mentions_generics(&input.ty, &type_params) {
+                    true => quote!(::std::string::String::from("_")),
+                    false => quote!(::std::format!("{:?}", #arg)),
+                },
+            );
+        let method_str = method.to_string();
+        let record = quote! {
+            self.__calls.lock().unwrap().push(#types_crate::MockCall {
+                method: #method_str,
+                args: ::std::vec![#(#recorded_args),*],
+            });
+        };
+        let ReturnType::Type(_, ty) = &sig.output else {
+            methods.push(quote!(#sig { #record }));
+            continue;
+        };
+        if mentions_generics(ty, &type_params) {
+            return Err(Error::new(
+                mock.span(),
+                format!(
+                    "the return type of `{method}` depends on its generic parameters, implement \
+                     it in the impl block yourself"
+                ),
+            ));
+        }
+        let mut ty = (**ty).clone();
+        concrete.visit_type_mut(&mut ty);
+        let returns = format_ident!("__{method}_returns");
+        let queue = format_ident!("queue_{method}");
+        let missing = format!(
+            "{mock}::{method} was called but no return value is queued, queue one with \
+             `{mock}::{queue}`"
+        );
+        queues.push((returns.clone(), ty.clone()));
+        let doc = format!("Queues a value for the next call to `{method}` to return.");
+        queue_fns.push(quote! {
+            #[doc = #doc]
+            pub fn #queue(&self, value: #ty) -> &Self {
+                self.#returns.lock().unwrap().push_back(value);
+                self
+            }
+        });
+        methods.push(quote! {
+            #sig {
+                #record
+                let value = self.#returns.lock().unwrap().pop_front();
+                value.unwrap_or_else(|| ::std::panic!(#missing))
+            }
+        });
+    }
+    for method in methods {
+        skeleton.items.push(parse_quote!(#method));
+    }
+    let queue_idents = queues.iter().map(|(ident, _)| ident);
+    let queue_fields = queues
+        .iter()
+        .map(|(ident, ty)| quote!(#ident: ::std::sync::Mutex<::std::collections::VecDeque<#ty>>));
+    let doc = format!(
+        "A mock of `{}` that records its calls and returns queued values.",
+        trait_item.ident
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        pub struct #mock {
+            __calls: ::std::sync::Mutex<::std::vec::Vec<#types_crate::MockCall>>,
+            #(#queue_fields,)*
+        }
+
+        impl #mock {
+            pub fn new() -> Self {
+                #mock {
+                    __calls: ::std::default::Default::default(),
+                    #(#queue_idents: ::std::default::Default::default(),)*
+                }
+            }
+
+            /// Every call made so far, in order.
+            pub fn calls(&self) -> ::std::vec::Vec<#types_crate::MockCall> {
+                self.__calls.lock().unwrap().clone()
+            }
+
+            /// The calls made to `method` so far, in order.
+            pub fn calls_to(&self, method: &str) -> ::std::vec::Vec<#types_crate::MockCall> {
+                self.calls()
+                    .into_iter()
+                    .filter(|call| call.method == method)
+                    .collect()
+            }
+
+            #(#queue_fns)*
+        }
+
+        impl ::std::default::Default for #mock {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #skeleton
+    })
+}
+
+/// The name of the mock, which is the self type of the impl block.
+fn mock_ident(skeleton: &ItemImpl) -> Result<Ident> {
+    if let Type::Path(ty) = &*skeleton.self_ty {
+        if ty.qself.is_none() && skeleton.generics.params.is_empty() {
+            if let Some(ident) = ty.path.get_ident() {
+                return Ok(ident.clone());
+            }
+        }
+    }
+    Err(Error::new_spanned(
+        &skeleton.self_ty,
+        "mock_of expects an impl for a plain struct name, i.e. `impl Trait for MockTrait {}`",
+    ))
+}
+
+/// Whether `ty` mentions one of the generic type parameters of a method or an `impl Trait`,
+/// neither of which the mock can store.
+fn mentions_generics(ty: &Type, params: &[Ident]) -> bool {
+    fn mentions(tokens: TokenStream2, params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "impl" || params.contains(&ident),
+            TokenTree::Group(group) => mentions(group.stream(), params),
+            _ => false,
+        })
+    }
+    mentions(ty.to_token_stream(), params)
+}
+
+/// Turns a return type into a type the mock can store: `Self` becomes the mock, associated
+/// types become the types the impl block assigns them, and all lifetimes become `'static`.
+struct Concrete {
+    mock: Ident,
+    assoc_types: Vec<(Ident, Type)>,
+}
+
+impl VisitMut for Concrete {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            let segments: Vec<&Ident> = path.path.segments.iter().map(|s| &s.ident).collect();
+            match segments[..] {
+                [this] if path.qself.is_none() && this == "Self" => {
+                    let mock = &self.mock;
+                    *ty = parse_quote!(#mock);
+                    return;
+                }
+                [this, assoc] if path.qself.is_none() && this == "Self" => {
+                    if let Some((_, assoc_ty)) =
+                        self.assoc_types.iter().find(|(ident, _)| ident == assoc)
+                    {
+                        *ty = assoc_ty.clone();
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(Lifetime::new("'static", Span::call_site()));
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        *lifetime = Lifetime::new("'static", Span::call_site());
+    }
+}
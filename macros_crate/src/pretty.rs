@@ -0,0 +1,60 @@
+//! Helpers that render syntax nodes the way `rustfmt` would, using `prettyplease`.
+//!
+//! `prettyplease` can only print whole files, so everything that isn't an item is wrapped in
+//! a throwaway item and then cut back out of the printed source.
+
+use quote::ToTokens;
+use syn::{parse_quote, Attribute, File, Generics, Item, Pat, Type, Visibility};
+
+/// Renders an item as formatted source, without a trailing newline.
+pub fn item(item: &Item) -> String {
+    let file = File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item.clone()],
+    };
+    prettyplease::unparse(&file).trim_end().to_string()
+}
+
+/// Prints `wrapper` and strips `prefix` and `suffix` back off again.
+fn unwrap(wrapper: Item, prefix: &str, suffix: &str) -> String {
+    let printed = item(&wrapper);
+    printed
+        .strip_prefix(prefix)
+        .and_then(|s| s.strip_suffix(suffix))
+        .unwrap_or(&printed)
+        .to_string()
+}
+
+/// Renders a type, i.e. `Vec<u8>` rather than `Vec < u8 >`.
+pub fn ty(ty: &Type) -> String {
+    unwrap(parse_quote!(type __T = #ty;), "type __T = ", ";")
+}
+
+/// Renders the generic parameters of an item including angle brackets, or `""` if there are
+/// none. Where clauses are not included.
+pub fn generics(generics: &Generics) -> String {
+    if generics.params.is_empty() {
+        return String::new();
+    }
+    let params = generics.to_token_stream();
+    unwrap(parse_quote!(struct __S #params;), "struct __S", ";")
+}
+
+/// Renders a visibility, or `""` for inherited (private) visibility.
+pub fn visibility(vis: &Visibility) -> String {
+    if let Visibility::Inherited = vis {
+        return String::new();
+    }
+    unwrap(parse_quote!(#vis struct __S;), "", " struct __S;")
+}
+
+/// Renders a single outer attribute, i.e. `#[derive(Debug)]`.
+pub fn attribute(attr: &Attribute) -> String {
+    unwrap(parse_quote!(#attr struct __S;), "", "\nstruct __S;")
+}
+
+/// Renders a pattern, such as the pattern of a fn parameter.
+pub fn pat(pat: &Pat) -> String {
+    unwrap(parse_quote!(fn __f(#pat: ()) {}), "fn __f(", ": ()) {}")
+}
@@ -0,0 +1,318 @@
+//! Derives JSON schemas from imported items, used by `json_schema!`.
+//!
+//! The schemas describe the JSON `serde` produces with its default settings, i.e. structs are
+//! objects, newtype structs are their inner value and enums are externally tagged.
+
+use proc_macro2::Span;
+use syn::{
+    Error, Expr, ExprLit, Fields, GenericArgument, Item, Lit, PathArguments, Result, Type, TypePath,
+};
+
+use crate::{compare, json::Json, pretty};
+
+/// The dialect the generated schemas declare in `$schema`.
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Where the `$ref`s to types that aren't known point, in the `$defs` of the document itself.
+const DEFS_POINTER: &str = "#/$defs/";
+
+/// Builds the schema document for a struct or enum, reporting any other kind of item at `span`.
+pub fn item_schema(item: &Item, span: Span) -> Result<Json> {
+    let (ident, body) = match item {
+        Item::Struct(item) => (&item.ident, fields_schema(&item.fields)),
+        Item::Enum(item) => {
+            let variants = item
+                .variants
+                .iter()
+                .map(|variant| {
+                    let name = variant.ident.to_string();
+                    match &variant.fields {
+                        Fields::Unit => Json::object([("const", Json::string(name))]),
+                        fields => object(vec![(name, fields_schema(fields), true)]),
+                    }
+                })
+                .collect();
+            (
+                &item.ident,
+                Json::object([("oneOf", Json::Array(variants))]),
+            )
+        }
+        item => {
+            return Err(Error::new(
+                span,
+                format!(
+                    "json_schema! only supports structs and enums, not {}s",
+                    compare::kind(item)
+                ),
+            ))
+        }
+    };
+    let mut entries = vec![
+        ("$schema".to_string(), Json::string(DIALECT)),
+        ("title".to_string(), Json::string(ident.to_string())),
+    ];
+    if let Json::Object(body) = body {
+        entries.extend(body);
+    }
+    let mut defs = Vec::new();
+    for (_, value) in &entries {
+        collect_defs(value, &mut defs);
+    }
+    if !defs.is_empty() {
+        let defs = defs
+            .into_iter()
+            .map(|name| (name, Json::Object(Vec::new())));
+        entries.push(("$defs".to_string(), Json::Object(defs.collect())));
+    }
+    Ok(Json::Object(entries))
+}
+
+/// Collects the names of the `$defs` the `$ref`s in `schema` point to, in the order they are
+/// first referenced. They are emitted as empty schemas, which accept any value, so that every
+/// reference resolves and can be narrowed down by whoever knows what the type looks like.
+fn collect_defs(schema: &Json, defs: &mut Vec<String>) {
+    match schema {
+        Json::Object(entries) => {
+            for (key, value) in entries {
+                match (key.as_str(), value) {
+                    ("$ref", Json::String(pointer)) => {
+                        let fragment = pointer.strip_prefix(DEFS_POINTER).unwrap_or(pointer);
+                        let name = percent_decode(fragment)
+                            .replace("~1", "/")
+                            .replace("~0", "~");
+                        if !defs.contains(&name) {
+                            defs.push(name);
+                        }
+                    }
+                    _ => collect_defs(value, defs),
+                }
+            }
+        }
+        Json::Array(values) => values.iter().for_each(|value| collect_defs(value, defs)),
+        _ => {}
+    }
+}
+
+/// Schema of the fields of a struct or enum variant.
+fn fields_schema(fields: &Fields) -> Json {
+    match fields {
+        Fields::Named(fields) => object(
+            fields
+                .named
+                .iter()
+                .map(|field| {
+                    let name = field.ident.as_ref().unwrap().to_string();
+                    (
+                        name,
+                        type_schema(&field.ty),
+                        option_inner(&field.ty).is_none(),
+                    )
+                })
+                .collect(),
+        ),
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => type_schema(&fields.unnamed[0].ty),
+        Fields::Unnamed(fields) => tuple(fields.unnamed.iter().map(|field| &field.ty)),
+        Fields::Unit => Json::object([("type", Json::string("null"))]),
+    }
+}
+
+/// An object schema with the given `(name, schema, required)` properties.
+fn object(properties: Vec<(String, Json, bool)>) -> Json {
+    let required = properties
+        .iter()
+        .filter(|(_, _, required)| *required)
+        .map(|(name, _, _)| Json::string(name.clone()))
+        .collect();
+    Json::object([
+        ("type", Json::string("object")),
+        (
+            "properties",
+            Json::Object(
+                properties
+                    .into_iter()
+                    .map(|(name, schema, _)| (name, schema))
+                    .collect(),
+            ),
+        ),
+        ("required", Json::Array(required)),
+    ])
+}
+
+/// A fixed-length array schema with one entry per element type.
+fn tuple<'a>(types: impl ExactSizeIterator<Item = &'a Type>) -> Json {
+    let len = types.len() as u64;
+    Json::object([
+        ("type", Json::string("array")),
+        ("prefixItems", Json::Array(types.map(type_schema).collect())),
+        ("minItems", Json::Number(len)),
+        ("maxItems", Json::Number(len)),
+    ])
+}
+
+/// Schema of a field type. Anything that isn't a primitive or a well-known std container
+/// becomes a `$ref` to the `$defs` entry named after the type as written.
+fn type_schema(ty: &Type) -> Json {
+    match ty {
+        Type::Path(path) => path_schema(path).unwrap_or_else(|| reference(ty)),
+        Type::Reference(reference) => type_schema(&reference.elem),
+        Type::Paren(paren) => type_schema(&paren.elem),
+        Type::Group(group) => type_schema(&group.elem),
+        Type::Slice(slice) => array(type_schema(&slice.elem)),
+        Type::Array(array_ty) => {
+            let mut schema = array(type_schema(&array_ty.elem));
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) = &array_ty.len
+            {
+                if let (Json::Object(entries), Ok(len)) = (&mut schema, len.base10_parse()) {
+                    entries.push(("minItems".to_string(), Json::Number(len)));
+                    entries.push(("maxItems".to_string(), Json::Number(len)));
+                }
+            }
+            schema
+        }
+        Type::Tuple(tuple_ty) if tuple_ty.elems.is_empty() => {
+            Json::object([("type", Json::string("null"))])
+        }
+        Type::Tuple(tuple_ty) => tuple(tuple_ty.elems.iter()),
+        ty => reference(ty),
+    }
+}
+
+fn path_schema(path: &TypePath) -> Option<Json> {
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    let args: Vec<&Type> = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let simple = |ty: &str| Json::object([("type", Json::string(ty))]);
+    let schema = match (segment.ident.to_string().as_str(), &args[..]) {
+        ("bool", []) => simple("boolean"),
+        ("u8" | "u16" | "u32" | "u64" | "u128" | "usize", []) => Json::object([
+            ("type", Json::string("integer")),
+            ("minimum", Json::Number(0)),
+        ]),
+        ("i8" | "i16" | "i32" | "i64" | "i128" | "isize", []) => simple("integer"),
+        ("f32" | "f64", []) => simple("number"),
+        ("String" | "str", []) => simple("string"),
+        ("char", []) => Json::object([
+            ("type", Json::string("string")),
+            ("minLength", Json::Number(1)),
+            ("maxLength", Json::Number(1)),
+        ]),
+        ("Option", [inner]) => Json::object([(
+            "anyOf",
+            Json::Array(vec![type_schema(inner), simple("null")]),
+        )]),
+        ("Box" | "Rc" | "Arc" | "Cow", [inner]) => type_schema(inner),
+        ("Vec" | "VecDeque" | "LinkedList", [inner]) => array(type_schema(inner)),
+        ("HashSet" | "BTreeSet", [inner]) => {
+            let mut schema = array(type_schema(inner));
+            if let Json::Object(entries) = &mut schema {
+                entries.push(("uniqueItems".to_string(), Json::Bool(true)));
+            }
+            schema
+        }
+        ("HashMap" | "BTreeMap", [_, value]) => Json::object([
+            ("type", Json::string("object")),
+            ("additionalProperties", type_schema(value)),
+        ]),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+fn array(items: Json) -> Json {
+    Json::object([("type", Json::string("array")), ("items", items)])
+}
+
+/// A `$ref` to the `$defs` entry named after `ty` as written. The name is escaped as a JSON
+/// Pointer token and then percent-encoded, since types like `Option<&'static str>` contain
+/// characters a URI fragment can't.
+fn reference(ty: &Type) -> Json {
+    let name = pretty::ty(ty).replace('~', "~0").replace('/', "~1");
+    let fragment = percent_encode(&name);
+    Json::object([("$ref", Json::string(format!("{DEFS_POINTER}{fragment}")))])
+}
+
+/// Percent-encodes every byte of `s` but the unreserved characters of RFC 3986.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::new();
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Reverses [`percent_encode`].
+fn percent_decode(s: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Returns `T` if `ty` is an `Option<T>`, which makes the field optional.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn references_are_valid_fragments() {
+        let ty: Type = parse_quote!(Pair<Vec<u8>, &'static str>);
+        let schema = type_schema(&ty);
+        assert_eq!(
+            schema.to_string(),
+            r##"{"$ref":"#/$defs/Pair%3CVec%3Cu8%3E%2C%20%26%27static%20str%3E"}"##
+        );
+        let mut defs = Vec::new();
+        collect_defs(&schema, &mut defs);
+        assert_eq!(defs, ["Pair<Vec<u8>, &'static str>"]);
+    }
+}
@@ -0,0 +1,238 @@
+//! Expansion snapshots of `make_item_const!` and `print_foreign_item!` as `user_crate` uses
+//! them, one hop at a time.
+//!
+//! The hops are produced by the code `rustc` would run for them: our own macros, and the
+//! `forward_tokens!`, `#[export_tokens]` and `forward_tokens_inner!` implementations in
+//! `macro_magic`. They are compared as syntax, pretty-printed, so how the tokens are spaced
+//! doesn't show. The `__export_tokens_tt_*` `macro_rules!` can only be invoked by `rustc`, so
+//! the snapshot shows its definition and spells out what its `$extra` arm expands to.
+//!
+//! Outside of a macro, `proc_macro2` stringifies tokens with its own fallback rather than the
+//! way `rustc` does, and the output of the hidden inner macros embeds such strings. So the
+//! snapshots end with a part that follows a line ending in [`COMPILED`], which is taken from
+//! the constants and catalog `user_crate` was actually compiled with and checked by the tests
+//! of `user_crate` rather than here.
+//!
+//! The snapshots live in `macros_crate/snapshots`. After an intended change, or a `macro_magic`
+//! bump, regenerate them with `SNAPSHOTS=overwrite cargo test --workspace` and review the diff.
+//!
+//! `user_crate` invokes our macros through `facade_crate`, so the snapshots follow the
+//! `__facade_*` entry points, which refer to `macro_magic` as `::facade_crate::macro_magic`.
+
+use std::{cell::Cell, env, fmt::Write, fs, path::PathBuf};
+
+use export_items::{is_export_tokens, item_attrs_mut};
+use macro_magic::mm_core::{
+    export_tokens_internal, forward_tokens_inner_internal, forward_tokens_internal,
+};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Expr, File, Item, ItemMacro, Macro, Path, Result, Token,
+};
+
+use crate::{
+    make_item_const_inner_internal, make_item_const_internal, print_foreign_item_inner_internal,
+    print_foreign_item_internal, Roots,
+};
+
+/// Where the snapshots and the crates they are taken from live, relative to `macros_crate`.
+const SNAPSHOT_DIR: &str = "snapshots";
+const USER_CRATE_SRC: &str = "../src/lib.rs";
+const FOREIGN_CRATE_SRC: &str = "../foreign_crate/src/lib.rs";
+
+/// Ends the line after which a snapshot holds what `user_crate` was compiled with.
+const COMPILED: &str = ", as compiled into user_crate";
+
+#[test]
+fn make_item_const_expansion() {
+    let (mut snapshot, target_call) =
+        expansion("make_item_const!(foreign_crate::StructTwo);", |tokens| {
+            make_item_const_internal(tokens, &Roots::facade())
+        });
+    make_item_const_inner_internal(target_call.tokens).unwrap();
+    let target = render(&target_call.path);
+    writeln!(snapshot, "\n// {target}! expands to{COMPILED}").unwrap();
+    assert_snapshot("make_item_const", &snapshot);
+}
+
+#[test]
+fn print_foreign_item_expansion() {
+    let (mut snapshot, target_call) =
+        expansion("print_foreign_item!(foreign_crate::StructOne);", |tokens| {
+            print_foreign_item_internal(tokens, &Roots::facade())
+        });
+    let recorded = Cell::new(false);
+    let expanded = print_foreign_item_inner_internal(target_call.tokens, "user_crate", |_| {
+        recorded.set(true);
+        Ok(PathBuf::new())
+    })
+    .unwrap();
+    let target = render(&target_call.path);
+    hop(&mut snapshot, &format!("{target}! expands to"), &expanded);
+    assert!(recorded.get(), "nothing was recorded in the catalog");
+    writeln!(
+        snapshot,
+        "\n// and records this line in the catalog{COMPILED}"
+    )
+    .unwrap();
+    assert_snapshot("print_foreign_item", &snapshot);
+}
+
+/// Follows `invocation`, which must appear in `user_crate`, through every hop of its expansion
+/// up to the call of our hidden inner macro, renders each of them and returns that call.
+fn expansion(
+    invocation: &str,
+    outer: impl FnOnce(TokenStream2) -> Result<TokenStream2>,
+) -> (String, Macro) {
+    let user_src = read(USER_CRATE_SRC);
+    assert!(
+        user_src.contains(invocation),
+        "`{invocation}` no longer appears in user_crate, snapshot an invocation that does"
+    );
+    let mut out = format!("// {invocation}\n");
+    let invocation: ItemMacro = syn::parse_str(invocation).unwrap();
+    let name = render(&invocation.mac.path);
+
+    let forward = outer(invocation.mac.tokens).unwrap();
+    hop(&mut out, &format!("{name}! expands to"), &forward);
+
+    let forward: Macro = syn::parse2(forward).unwrap();
+    let export_call = forward_tokens_internal(forward.tokens).unwrap();
+    hop(&mut out, "forward_tokens! expands to", &export_call);
+
+    let export_call: Macro = syn::parse2(export_call).unwrap();
+    let export_macro = export_call.path.segments.last().unwrap().ident.clone();
+    let (attr, item, definition) = exported_item(&export_macro);
+    hop(&mut out, &format!("{} defines", render(&attr)), &definition);
+    let ExportCall {
+        target,
+        callback,
+        extra,
+    } = syn::parse2(export_call.tokens).unwrap();
+    // the `$extra` arm of the definition above
+    let inner_call = quote! {
+        #callback! {
+            #target,
+            #item,
+            #extra
+        }
+    };
+    hop(
+        &mut out,
+        &format!("{export_macro}! expands to"),
+        &inner_call,
+    );
+
+    let inner_call: Macro = syn::parse2(inner_call).unwrap();
+    let target_call = forward_tokens_inner_internal(inner_call.tokens).unwrap();
+    hop(&mut out, "forward_tokens_inner! expands to", &target_call);
+
+    (out, syn::parse2(target_call).unwrap())
+}
+
+/// The arguments `forward_tokens!` passes to an `__export_tokens_tt_*` macro.
+struct ExportCall {
+    target: Path,
+    callback: Path,
+    extra: Expr,
+}
+
+impl Parse for ExportCall {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let target = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let callback = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let extra = input.parse()?;
+        Ok(ExportCall {
+            target,
+            callback,
+            extra,
+        })
+    }
+}
+
+/// Finds the item in `foreign_crate` whose `#[export_tokens]` defines `export_macro`, returning
+/// the attribute, the item as the attribute receives it, and what the attribute expands to.
+fn exported_item(export_macro: &syn::Ident) -> (Attribute, Item, TokenStream2) {
+    let file: File = syn::parse_str(&read(FOREIGN_CRATE_SRC)).unwrap();
+    let mut pending = file.items;
+    while let Some(mut item) = pending.pop() {
+        if let Item::Mod(module) = &item {
+            if let Some((_, items)) = &module.content {
+                pending.extend(items.iter().cloned());
+            }
+        }
+        let Some(attrs) = item_attrs_mut(&mut item) else {
+            continue;
+        };
+        let Some(position) = attrs.iter().position(is_export_tokens) else {
+            continue;
+        };
+        let attr = attrs.remove(position);
+        let args = match &attr.meta {
+            syn::Meta::List(list) => list.tokens.clone(),
+            _ => TokenStream2::new(),
+        };
+        let definition = export_tokens_internal(args, item.to_token_stream()).unwrap();
+        let defined: File = syn::parse2(definition.clone()).unwrap();
+        if let Some(Item::Macro(ItemMacro {
+            ident: Some(ident), ..
+        })) = defined.items.first()
+        {
+            if ident == export_macro {
+                return (attr, item, definition);
+            }
+        }
+    }
+    panic!("no item in foreign_crate defines `{export_macro}`");
+}
+
+fn hop(out: &mut String, title: &str, tokens: &TokenStream2) {
+    writeln!(out, "\n// {title}").unwrap();
+    match syn::parse2::<File>(tokens.clone()) {
+        Ok(file) if file.items.is_empty() => out.push_str("// nothing\n"),
+        Ok(file) => out.push_str(&prettyplease::unparse(&file)),
+        Err(_) => writeln!(out, "{tokens}").unwrap(),
+    }
+}
+
+/// Renders short paths and attributes the way they would be written by hand.
+fn render(tokens: &impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+fn read(relative: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative);
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {}: {err}", path.display()))
+}
+
+/// Compares `actual` with the checked-in snapshot up to the end of its [`COMPILED`] line, or
+/// overwrites that part of the snapshot if `SNAPSHOTS=overwrite` is set. What follows the line
+/// is left to `user_crate`.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(SNAPSHOT_DIR)
+        .join(format!("{name}.snap"));
+    let snapshot = fs::read_to_string(&path).unwrap_or_default();
+    let compiled = match snapshot.find(COMPILED) {
+        Some(start) => snapshot[start + COMPILED.len()..]
+            .strip_prefix('\n')
+            .unwrap_or_default(),
+        None => "",
+    };
+    if env::var("SNAPSHOTS").as_deref() == Ok("overwrite") {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("{actual}{compiled}")).unwrap();
+        return;
+    }
+    let expected = &snapshot[..snapshot.len() - compiled.len()];
+    assert!(
+        expected == actual,
+        "the expansion of `{name}` doesn't match {}, review the change and run \
+         `SNAPSHOTS=overwrite cargo test -p macros_crate` to accept it:\n{actual}",
+        path.display()
+    );
+}
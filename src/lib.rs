@@ -1,9 +1,829 @@
-#[macro_magic::use_proc]
-use macros_crate::make_item_const;
+#[cfg(test)]
+use facade_crate::types_crate::{FieldDocs, FieldMeta, ItemKind, MockCall, VariantMeta};
+
+#[facade_crate::use_proc]
+use facade_crate::assert_item_fingerprint;
+#[facade_crate::use_proc]
+use facade_crate::assert_items_equivalent;
+#[facade_crate::use_attr]
+use facade_crate::assert_same_shape;
+#[facade_crate::use_attr]
+use facade_crate::copy_fields_from;
+#[facade_crate::use_attr]
+use facade_crate::delegate_trait;
+#[facade_crate::use_attr]
+use facade_crate::extend_enum;
+#[facade_crate::use_proc]
+use facade_crate::field_names;
+#[facade_crate::use_proc]
+use facade_crate::field_types;
+#[facade_crate::use_proc]
+use facade_crate::inline_foreign_fn;
+#[facade_crate::use_proc]
+use facade_crate::item_docs;
+#[facade_crate::use_proc]
+use facade_crate::item_fingerprint;
+#[facade_crate::use_proc]
+use facade_crate::item_meta;
+#[facade_crate::use_proc]
+use facade_crate::json_schema;
+#[facade_crate::use_proc]
+use facade_crate::list_module_items;
+#[facade_crate::use_proc]
+use facade_crate::make_item_const;
+#[facade_crate::use_proc]
+use facade_crate::make_item_source;
+#[facade_crate::use_proc]
+use facade_crate::make_items_const;
+#[facade_crate::use_proc]
+use facade_crate::mirror_item;
+#[facade_crate::use_attr]
+use facade_crate::mock_of;
+#[facade_crate::use_proc]
+use facade_crate::module_item_src;
+#[facade_crate::use_proc]
+use facade_crate::print_foreign_item;
 
 make_item_const!(foreign_crate::StructTwo);
+make_item_const!(foreign_crate::StructOne as STRUCT_ONE_SRC);
+make_item_const!(foreign_crate::StructTwo as ITEM_SRC);
+make_item_const!(foreign_crate::ErrorKind);
+make_item_const!(foreign_crate::Storage);
+make_item_const!(foreign_crate::area);
+make_item_const!(foreign_crate::MAX_RETRIES);
+make_item_const!(foreign_crate::GREETING);
+make_item_const!(foreign_crate::Grid);
+make_item_const!(foreign_crate::IntOrFloat);
+make_item_const!(foreign_crate::CounterImpl);
+make_item_const!(foreign_crate::DescribeCounter);
+make_item_const!(foreign_crate::HashMapUse);
+make_item_const!(foreign_crate::FmtUse);
+make_item_source!(foreign_crate::StructTwo);
+make_item_source!(foreign_crate::StructOne as STRUCT_ONE_FORMATTED);
+item_meta!(foreign_crate::StructOne);
+item_meta!(foreign_crate::Shape);
+item_meta!(foreign_crate::Describe as DESCRIBE);
+item_meta!(foreign_crate::area);
+make_items_const!([foreign_crate::StructOne, foreign_crate::StructTwo]);
+make_items_const!([
+    foreign_crate::Shape,
+    foreign_crate::Describe,
+    foreign_crate::area
+] as THIRD_MOD_SRC);
+make_items_const!(foreign_crate::StructOne as ONE_SRC, foreign_crate::Shape);
+print_foreign_item!(foreign_crate::StructOne);
+item_fingerprint!(foreign_crate::StructOne);
+item_fingerprint!(foreign_crate::StructTwo);
+assert_item_fingerprint!(foreign_crate::StructTwo, "621995b2909e44f8");
+assert_items_equivalent!(foreign_crate::PointOne, foreign_crate::PointTwo);
+mirror_item!(
+    #[derive(Debug, Clone, PartialEq)]
+    foreign_crate::StructOne as LocalOne
+);
+mirror_item!(
+    #[derive(Debug, PartialEq)]
+    foreign_crate::Shape
+);
+field_names!(foreign_crate::PointOne);
+field_types!(foreign_crate::PointOne);
+field_names!(foreign_crate::Pair);
+field_types!(foreign_crate::Pair as PAIR_TYPES);
+field_names!(foreign_crate::Marker);
+field_names!(foreign_crate::Shape, Circle);
+field_types!(foreign_crate::Shape, Rect);
+field_names!(foreign_crate::Shape, Empty as EMPTY_FIELDS);
+json_schema!(foreign_crate::StructTwo);
+json_schema!(foreign_crate::Shape);
+json_schema!(foreign_crate::Pair as PAIR_SCHEMA);
+json_schema!(foreign_crate::Marker);
+item_docs!(foreign_crate::Shape);
+item_docs!(foreign_crate::Pair);
+item_docs!(foreign_crate::StructOne as STRUCT_ONE_DOCS);
+list_module_items!(foreign_crate::FirstMod);
+list_module_items!(foreign_crate::FourthMod as FOURTH_MOD_ITEMS);
+module_item_src!(foreign_crate::FirstMod, MyStruct);
+module_item_src!(foreign_crate::FirstMod, Point as FIRST_POINT_SRC);
+make_item_const!(foreign_crate::Config as CONFIG_TOKENS);
+make_item_const!(foreign_crate::Config, cfg);
+#[cfg(feature = "extra")]
+make_item_const!(foreign_crate::ExtraOnly);
+inline_foreign_fn!(pub foreign_crate::classify as classify_status);
+inline_foreign_fn!(#[inline] pub foreign_crate::digits as count_digits);
+item_meta!(foreign_crate::Config, cfg);
+
+#[copy_fields_from(foreign_crate::StructOne)]
+#[derive(Debug, PartialEq)]
+struct Local {
+    extra: u8,
+}
+
+/// Redeclares `field1`, which `StructOne` declares as a `usize`.
+#[copy_fields_from(foreign_crate::StructOne)]
+#[derive(Debug)]
+struct Overridden {
+    label: &'static str,
+    field1: u8,
+}
+
+#[copy_fields_from(foreign_crate::PointTwo)]
+#[derive(Debug)]
+struct UnitPoint;
+
+pub struct Verbose;
+
+impl foreign_crate::Describe for Verbose {
+    const NAME: &'static str = "verbose";
+    type Output = String;
+
+    fn describe(&self, verbose: bool) -> String {
+        match verbose {
+            true => "a verbose description".to_string(),
+            false => "short".to_string(),
+        }
+    }
+}
+
+#[delegate_trait(foreign_crate::Describe, to = inner)]
+pub struct Wrapper {
+    pub label: &'static str,
+    pub inner: Verbose,
+}
+
+#[delegate_trait(foreign_crate::Describe, to = 0)]
+pub struct Wrapped<T>(pub T);
+
+#[mock_of(foreign_crate::Describe)]
+impl foreign_crate::Describe for MockDescribe {
+    const NAME: &'static str = "mock";
+    type Output = String;
+}
+
+#[mock_of(foreign_crate::Storage)]
+impl foreign_crate::Storage for MockStorage {
+    fn clear(&mut self) {}
+}
+
+/// A wire-compatible copy of `foreign_crate::StructTwo`.
+#[assert_same_shape(foreign_crate::StructTwo)]
+#[derive(Debug, PartialEq)]
+pub struct WireTwo {
+    pub field1: bool,
+}
+
+#[assert_same_shape(foreign_crate::Pair)]
+pub struct WirePair(pub u8, pub Option<String>);
+
+#[extend_enum(foreign_crate::ErrorKind, from)]
+#[derive(Debug, PartialEq)]
+pub enum LocalErrorKind {
+    Timeout,
+}
+
+#[extend_enum(foreign_crate::Shape)]
+#[derive(Debug, PartialEq)]
+pub enum Shapes<T: Copy> {
+    Triangle(T, T, T),
+}
+
+#[extend_enum(foreign_crate::Reply, from)]
+#[derive(Debug, PartialEq)]
+pub enum LocalReply<T, E> {
+    Failed(E),
+}
+
+/// Collapses the line breaks `TokenStream::to_string()` inserts into long items.
+#[cfg(test)]
+fn squash_whitespace(src: &str) -> String {
+    src.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
 #[test]
 fn test_make_item_const() {
     assert_eq!(ITEM_SRC, "struct MyStruct { field1 : bool, }");
 }
+
+#[test]
+fn test_make_item_const_default_name() {
+    assert_eq!(STRUCT_TWO_SRC, "struct MyStruct { field1 : bool, }");
+}
+
+#[test]
+fn test_make_item_const_named() {
+    assert_eq!(STRUCT_ONE_SRC, "struct MyStruct { field1 : usize, }");
+}
+
+#[test]
+fn test_make_item_const_enum() {
+    assert_eq!(
+        squash_whitespace(ERROR_KIND_SRC),
+        "#[derive(Clone, Debug, PartialEq)] pub enum ErrorKind \
+         { NotFound, PermissionDenied { path : String }, Other(u16), }"
+    );
+}
+
+#[test]
+fn test_make_item_const_trait() {
+    assert_eq!(
+        squash_whitespace(STORAGE_SRC),
+        "pub trait Storage \
+         { fn get(& self, key : & str) -> Option < & str > ; \
+         fn set(& mut self, key : String, value : String); fn clear(& mut self); }"
+    );
+}
+
+#[test]
+fn test_make_item_const_fn() {
+    assert_eq!(
+        AREA_SRC,
+        "pub(crate) fn area(width : usize, height : usize) -> usize { width * height }"
+    );
+}
+
+#[test]
+fn test_make_item_const_const() {
+    assert_eq!(MAX_RETRIES_SRC, "pub const MAX_RETRIES : u32 = 3;");
+}
+
+#[test]
+fn test_make_item_const_static() {
+    assert_eq!(GREETING_SRC, "pub static GREETING : & str = \"hello\";");
+}
+
+#[test]
+fn test_make_item_const_type_alias() {
+    assert_eq!(GRID_SRC, "pub type Grid = Vec < Vec < u8 > > ;");
+}
+
+#[test]
+fn test_make_item_const_union() {
+    assert_eq!(
+        INT_OR_FLOAT_SRC,
+        "#[repr(C)] pub union IntOrFloat { pub int : u32, pub float : f32, }"
+    );
+}
+
+#[test]
+fn test_make_item_const_impl() {
+    assert_eq!(
+        COUNTER_IMPL_SRC,
+        "impl Counter \
+         { pub fn increment(& mut self) -> u32 { self.0 += 1; self.0 } }"
+    );
+    assert_eq!(
+        squash_whitespace(DESCRIBE_COUNTER_SRC),
+        "impl Describe for Counter \
+         { const NAME : & 'static str = \"counter\"; type Output = u32; \
+         fn describe(& self, _verbose : bool) -> u32 { self.0 } }"
+    );
+}
+
+#[test]
+fn test_make_item_const_use() {
+    assert_eq!(HASH_MAP_USE_SRC, "use std :: collections :: HashMap;");
+    assert_eq!(FMT_USE_SRC, "use std :: fmt :: { self, Display };");
+}
+
+#[test]
+fn test_make_item_source() {
+    assert_eq!(STRUCT_TWO_SOURCE, "struct MyStruct {\n    field1: bool,\n}");
+    assert_eq!(
+        STRUCT_ONE_FORMATTED,
+        "struct MyStruct {\n    field1: usize,\n}"
+    );
+}
+
+#[test]
+fn test_item_meta_struct() {
+    assert_eq!(STRUCT_ONE_META.kind, ItemKind::Struct);
+    assert_eq!(STRUCT_ONE_META.ident, "MyStruct");
+    assert_eq!(STRUCT_ONE_META.generics, "");
+    assert_eq!(STRUCT_ONE_META.visibility, "");
+    assert!(STRUCT_ONE_META.attributes.is_empty());
+    assert_eq!(
+        STRUCT_ONE_META.fields,
+        &[FieldMeta {
+            name: "field1",
+            ty: "usize"
+        }]
+    );
+}
+
+#[test]
+fn test_item_meta_enum() {
+    assert_eq!(SHAPE_META.kind, ItemKind::Enum);
+    assert_eq!(SHAPE_META.ident, "Shape");
+    assert_eq!(SHAPE_META.generics, "<T: Copy>");
+    assert_eq!(SHAPE_META.visibility, "pub");
+    assert_eq!(SHAPE_META.attributes, &["#[derive(Copy, Clone, Debug)]"]);
+    assert_eq!(
+        SHAPE_META.variants,
+        &[
+            VariantMeta {
+                name: "Circle",
+                fields: &[FieldMeta {
+                    name: "radius",
+                    ty: "T"
+                }],
+            },
+            VariantMeta {
+                name: "Rect",
+                fields: &[
+                    FieldMeta { name: "0", ty: "T" },
+                    FieldMeta { name: "1", ty: "T" }
+                ],
+            },
+            VariantMeta {
+                name: "Empty",
+                fields: &[],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_item_meta_trait() {
+    assert_eq!(DESCRIBE.kind, ItemKind::Trait);
+    assert_eq!(DESCRIBE.ident, "Describe");
+    let kinds: Vec<_> = DESCRIBE.items.iter().map(|item| item.kind).collect();
+    assert_eq!(kinds, [ItemKind::Const, ItemKind::Type, ItemKind::Fn]);
+    assert_eq!(DESCRIBE.items[0].ty, Some("&'static str"));
+    assert_eq!(DESCRIBE.items[1].ty, None);
+    let describe = &DESCRIBE.items[2];
+    assert_eq!(describe.ident, "describe");
+    assert_eq!(
+        describe.fields,
+        &[
+            FieldMeta {
+                name: "self",
+                ty: "&Self"
+            },
+            FieldMeta {
+                name: "verbose",
+                ty: "bool"
+            },
+        ]
+    );
+    assert_eq!(describe.ty, Some("Self::Output"));
+}
+
+#[test]
+fn test_item_meta_fn() {
+    assert_eq!(AREA_META.kind, ItemKind::Fn);
+    assert_eq!(AREA_META.ident, "area");
+    assert_eq!(AREA_META.visibility, "pub(crate)");
+    assert_eq!(AREA_META.fields.len(), 2);
+    assert_eq!(AREA_META.fields[1].name, "height");
+    assert_eq!(AREA_META.ty, Some("usize"));
+}
+
+#[test]
+fn test_item_fingerprint() {
+    assert_eq!(STRUCT_TWO_FINGERPRINT, "621995b2909e44f8");
+    assert_eq!(STRUCT_ONE_FINGERPRINT.len(), 16);
+    assert_ne!(STRUCT_ONE_FINGERPRINT, STRUCT_TWO_FINGERPRINT);
+}
+
+#[test]
+fn test_make_items_const_array() {
+    assert_eq!(
+        ITEMS_SRC,
+        &[
+            "struct MyStruct { field1 : usize, }",
+            "struct MyStruct { field1 : bool, }"
+        ]
+    );
+    assert_eq!(THIRD_MOD_SRC.len(), 3);
+    assert!(THIRD_MOD_SRC[0].contains("enum Shape"));
+    assert!(THIRD_MOD_SRC[1].contains("trait Describe"));
+    assert!(THIRD_MOD_SRC[2].contains("fn area"));
+}
+
+#[test]
+fn test_make_items_const_named() {
+    assert_eq!(ONE_SRC, "struct MyStruct { field1 : usize, }");
+    assert!(SHAPE_SRC.contains("enum Shape"));
+}
+
+/// The catalog `print_foreign_item!` wrote to while this crate was compiled.
+#[cfg(test)]
+fn read_catalog() -> String {
+    let catalog = match option_env!("FOREIGN_ITEM_CATALOG") {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::path::PathBuf::from(
+            option_env!("CARGO_TARGET_DIR")
+                .unwrap_or(concat!(env!("CARGO_MANIFEST_DIR"), "/target")),
+        )
+        .join("foreign_item_catalog.jsonl"),
+    };
+    std::fs::read_to_string(catalog).unwrap()
+}
+
+#[test]
+fn test_print_foreign_item_catalog() {
+    assert!(read_catalog().lines().any(|line| line
+        == "{\"crate\":\"user_crate\",\"export_name\":\"StructOne\",\
+            \"path\":\"foreign_crate::StructOne\",\
+            \"tokens\":\"struct MyStruct { field1 : usize, }\"}"));
+}
+
+/// Compares `compiled` with the end of the `macros_crate` expansion snapshot called `name`,
+/// which follows its `, as compiled into user_crate` line, or overwrites it if
+/// `SNAPSHOTS=overwrite` is set. `macros_crate` checks the rest of the snapshot, but outside of
+/// `rustc` it can't stringify tokens the way our hidden inner macros do here.
+#[cfg(test)]
+fn assert_compiled_snapshot(name: &str, compiled: &str) {
+    let path = format!(
+        "{}/macros_crate/snapshots/{name}.snap",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let snapshot = std::fs::read_to_string(&path).unwrap();
+    let marker = ", as compiled into user_crate\n";
+    let Some(start) = snapshot.find(marker) else {
+        panic!("{path} has no line ending in `{}`", marker.trim_end());
+    };
+    let (expansion, expected) = snapshot.split_at(start + marker.len());
+    if std::env::var("SNAPSHOTS").as_deref() == Ok("overwrite") {
+        std::fs::write(&path, format!("{expansion}{compiled}")).unwrap();
+        return;
+    }
+    assert!(
+        expected == compiled,
+        "what user_crate was compiled with doesn't match the end of {path}, review the change \
+         and run `SNAPSHOTS=overwrite cargo test --workspace` to accept it:\n{compiled}"
+    );
+}
+
+#[test]
+fn test_make_item_const_snapshot() {
+    assert_compiled_snapshot(
+        "make_item_const",
+        &format!("const STRUCT_TWO_SRC: &'static str = {STRUCT_TWO_SRC:?};\n"),
+    );
+}
+
+#[test]
+fn test_print_foreign_item_snapshot() {
+    let catalog = read_catalog();
+    let line = catalog
+        .lines()
+        .rfind(|line| line.starts_with("{\"crate\":\"user_crate\",\"export_name\":\"StructOne\","))
+        .unwrap();
+    assert_compiled_snapshot("print_foreign_item", &format!("{line}\n"));
+}
+
+#[test]
+fn test_mirror_item() {
+    let one = LocalOne { field1: 3 };
+    assert_eq!(one.clone(), LocalOne { field1: 3 });
+    assert_eq!(format!("{:?}", one), "LocalOne { field1: 3 }");
+    let shape: Shape<u8> = Shape::Rect(2, 3);
+    assert_ne!(shape, Shape::Empty);
+    assert_eq!(
+        format!("{:?}", Shape::Circle { radius: 1.5 }),
+        "Circle { radius: 1.5 }"
+    );
+}
+
+#[test]
+fn test_field_names_and_types() {
+    assert_eq!(POINT_ONE_FIELD_NAMES, &["x", "y"]);
+    assert_eq!(POINT_ONE_FIELD_TYPES, &["i32", "i32"]);
+    assert_eq!(PAIR_FIELD_NAMES, &["0", "1"]);
+    assert_eq!(PAIR_TYPES, &["u8", "Option<String>"]);
+    assert!(MARKER_FIELD_NAMES.is_empty());
+    assert_eq!(SHAPE_CIRCLE_FIELD_NAMES, &["radius"]);
+    assert_eq!(SHAPE_RECT_FIELD_TYPES, &["T", "T"]);
+    assert!(EMPTY_FIELDS.is_empty());
+}
+
+#[test]
+fn test_json_schema_struct() {
+    assert_eq!(
+        STRUCT_TWO_SCHEMA,
+        concat!(
+            r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","title":"MyStruct","#,
+            r#""type":"object","properties":{"field1":{"type":"boolean"}},"required":["field1"]}"#
+        )
+    );
+    assert_eq!(
+        MARKER_SCHEMA,
+        concat!(
+            r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","title":"Marker","#,
+            r#""type":"null"}"#
+        )
+    );
+}
+
+#[test]
+fn test_json_schema_tuple_struct() {
+    assert!(
+        PAIR_SCHEMA.contains(r#""type":"array","prefixItems":[{"type":"integer","minimum":0},"#)
+    );
+    assert!(PAIR_SCHEMA.contains(r#"{"anyOf":[{"type":"string"},{"type":"null"}]}]"#));
+    assert!(PAIR_SCHEMA.ends_with(r#""minItems":2,"maxItems":2}"#));
+}
+
+#[test]
+fn test_json_schema_enum() {
+    assert_eq!(
+        SHAPE_SCHEMA,
+        concat!(
+            r##"{"$schema":"https://json-schema.org/draft/2020-12/schema","title":"Shape","oneOf":["##,
+            r##"{"type":"object","properties":{"Circle":{"type":"object","##,
+            r##""properties":{"radius":{"$ref":"#/$defs/T"}},"required":["radius"]}},"##,
+            r##""required":["Circle"]},"##,
+            r##"{"type":"object","properties":{"Rect":{"type":"array","##,
+            r##""prefixItems":[{"$ref":"#/$defs/T"},{"$ref":"#/$defs/T"}],"minItems":2,"maxItems":2}},"##,
+            r##""required":["Rect"]},"##,
+            r##"{"const":"Empty"}],"$defs":{"T":{}}}"##
+        )
+    );
+}
+
+#[test]
+fn test_copy_fields_from() {
+    let local = Local {
+        field1: 3,
+        extra: 4,
+    };
+    assert_eq!(format!("{local:?}"), "Local { field1: 3, extra: 4 }");
+    let point = UnitPoint { x: -1, y: 2 };
+    assert_eq!(format!("{point:?}"), "UnitPoint { x: -1, y: 2 }");
+}
+
+#[test]
+fn test_copy_fields_from_conflicting_names() {
+    let overridden = Overridden {
+        label: "small",
+        field1: u8::MAX,
+    };
+    let widened: usize = overridden.field1.into();
+    assert_eq!((widened, overridden.label), (255, "small"));
+    // the local field keeps the position of the imported one
+    assert_eq!(
+        format!("{overridden:?}"),
+        "Overridden { field1: 255, label: \"small\" }"
+    );
+    assert_eq!(
+        std::mem::size_of::<Overridden>(),
+        std::mem::size_of::<(&str, u8)>()
+    );
+}
+
+#[test]
+fn test_delegate_trait() {
+    use foreign_crate::Describe;
+
+    let wrapper = Wrapper {
+        label: "wrapper",
+        inner: Verbose,
+    };
+    assert_eq!(wrapper.label, "wrapper");
+    assert_eq!(<Wrapper as Describe>::NAME, "verbose");
+    assert_eq!(wrapper.describe(true), "a verbose description");
+    let output: <Wrapper as Describe>::Output = wrapper.describe(false);
+    assert_eq!(output, "short");
+}
+
+#[test]
+fn test_delegate_trait_generic_wrapper() {
+    use foreign_crate::Describe;
+
+    let wrapped = Wrapped(Wrapped(Verbose));
+    assert_eq!(<Wrapped<Wrapped<Verbose>> as Describe>::NAME, "verbose");
+    assert_eq!(wrapped.describe(false), "short");
+}
+
+#[test]
+fn test_mock_of() {
+    use foreign_crate::Describe;
+
+    let mock = MockDescribe::new();
+    mock.queue_describe("first".to_string())
+        .queue_describe("second".to_string());
+    assert_eq!(MockDescribe::NAME, "mock");
+    assert_eq!(mock.describe(true), "first");
+    assert_eq!(mock.describe(false), "second");
+    assert_eq!(
+        mock.calls(),
+        [
+            MockCall {
+                method: "describe",
+                args: vec!["true".to_string()]
+            },
+            MockCall {
+                method: "describe",
+                args: vec!["false".to_string()]
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_mock_of_storage() {
+    use foreign_crate::Storage;
+
+    let mut mock = MockStorage::default();
+    mock.set("key".to_string(), "value".to_string());
+    mock.queue_get(Some("value")).queue_get(None);
+    assert_eq!(mock.get("key"), Some("value"));
+    assert_eq!(mock.get("other"), None);
+    mock.clear();
+    let gets: Vec<_> = mock.calls_to("get").into_iter().map(|c| c.args).collect();
+    assert_eq!(gets, [["\"key\""], ["\"other\""]]);
+    assert_eq!(mock.calls_to("set")[0].args, ["\"key\"", "\"value\""]);
+    // `clear` is implemented by hand, so it isn't recorded
+    assert_eq!(mock.calls().len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "MockDescribe::describe was called but no return value is queued")]
+fn test_mock_of_nothing_queued() {
+    use foreign_crate::Describe;
+
+    MockDescribe::new().describe(true);
+}
+
+#[test]
+fn test_assert_same_shape() {
+    assert_eq!(WireTwo { field1: true }, WireTwo { field1: true });
+    let pair = WirePair(1, None);
+    assert_eq!((pair.0, pair.1), (1, None));
+}
+
+#[test]
+fn test_extend_enum_from() {
+    use foreign_crate::ErrorKind;
+
+    assert_eq!(
+        LocalErrorKind::from(ErrorKind::NotFound),
+        LocalErrorKind::NotFound
+    );
+    assert_eq!(
+        LocalErrorKind::from(ErrorKind::PermissionDenied {
+            path: "/etc".to_string()
+        }),
+        LocalErrorKind::PermissionDenied {
+            path: "/etc".to_string()
+        }
+    );
+    assert_eq!(
+        LocalErrorKind::from(ErrorKind::Other(7)),
+        LocalErrorKind::Other(7)
+    );
+    assert_ne!(LocalErrorKind::Timeout, LocalErrorKind::NotFound);
+}
+
+#[test]
+fn test_extend_enum_from_with_extra_generics() {
+    use foreign_crate::Reply;
+
+    assert_eq!(
+        LocalReply::<u8, String>::from(Reply::Value(3)),
+        LocalReply::Value(3)
+    );
+    assert_eq!(
+        LocalReply::<u8, String>::from(Reply::Busy),
+        LocalReply::Busy
+    );
+    let failed: LocalReply<u8, String> = LocalReply::Failed("timeout".to_string());
+    assert_ne!(failed, LocalReply::Busy);
+}
+
+#[test]
+fn test_extend_enum_variant_names() {
+    assert_eq!(
+        Shapes::<u8>::UPSTREAM_VARIANTS,
+        &["Circle", "Rect", "Empty"]
+    );
+    assert_ne!(Shapes::Triangle(1, 2, 3), Shapes::Rect(1, 2));
+    assert_eq!(Shapes::Circle { radius: 1 }, Shapes::Circle { radius: 1 });
+}
+
+#[test]
+fn test_list_module_items() {
+    assert_eq!(
+        FIRST_MOD_ITEMS,
+        &[("MyStruct", ItemKind::Struct), ("Point", ItemKind::Struct)]
+    );
+    let kinds: Vec<_> = FOURTH_MOD_ITEMS.iter().map(|(_, kind)| *kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ItemKind::Use,
+            ItemKind::Const,
+            ItemKind::Static,
+            ItemKind::Type,
+            ItemKind::Union,
+            ItemKind::Struct,
+            ItemKind::Impl,
+            ItemKind::Impl,
+            ItemKind::Use,
+            ItemKind::Use,
+        ]
+    );
+    assert_eq!(FOURTH_MOD_ITEMS[7], ("Describe", ItemKind::Impl));
+    assert_eq!(FOURTH_MOD_ITEMS[6].0, "");
+}
+
+#[test]
+fn test_module_item_src() {
+    assert_eq!(
+        FIRST_MOD_MY_STRUCT_SRC,
+        "#[macro_magic :: export_tokens(StructOne)] struct MyStruct { field1 : usize, }"
+    );
+    assert_eq!(
+        squash_whitespace(FIRST_POINT_SRC),
+        "#[macro_magic :: export_tokens(PointOne)] struct Point { x : i32, y : i32, }"
+    );
+}
+
+#[test]
+fn test_item_docs_enum() {
+    assert_eq!(
+        SHAPE_DOCS.item,
+        "A shape with dimensions of type `T`.\n\nCovers generics and every style of variant."
+    );
+    assert!(SHAPE_DOCS.fields.is_empty());
+    let circle = &SHAPE_DOCS.variants[0];
+    assert_eq!(circle.docs, "A circle, described by its radius.");
+    assert_eq!(
+        circle.fields,
+        &[FieldDocs {
+            name: "radius",
+            docs: "The distance from the center to the edge."
+        }]
+    );
+    assert_eq!(SHAPE_DOCS.variants[1].fields.len(), 2);
+    assert_eq!(SHAPE_DOCS.variants[2].name, "Empty");
+    assert_eq!(SHAPE_DOCS.variants[2].docs, "");
+}
+
+#[test]
+fn test_item_docs_struct() {
+    assert_eq!(PAIR_DOCS.item, "A byte with an optional label.");
+    assert_eq!(
+        PAIR_DOCS.fields,
+        &[
+            FieldDocs {
+                name: "0",
+                docs: "The byte."
+            },
+            FieldDocs {
+                name: "1",
+                docs: ""
+            },
+        ]
+    );
+    assert_eq!(STRUCT_ONE_DOCS.item, "");
+}
+
+#[test]
+fn test_make_item_const_sees_cfg_attributes() {
+    let tokens = squash_whitespace(CONFIG_TOKENS);
+    assert!(tokens.contains(r#"#[cfg(feature = "extra")] pub verbose : bool"#));
+    assert!(tokens.contains(r#"#[cfg(not(feature = "extra"))] pub quiet : bool"#));
+    // `#[cfg_attr]`s on the item itself were already evaluated for `foreign_crate`
+    assert!(!tokens.contains("cfg_attr"));
+}
+
+#[cfg(not(feature = "extra"))]
+#[test]
+fn test_make_item_const_cfg() {
+    assert_eq!(
+        squash_whitespace(CONFIG_SRC),
+        "#[doc = \" Settings whose fields depend on the `extra` feature.\"] pub struct Config { \
+         pub name : String, pub quiet : bool }"
+    );
+    let fields: Vec<&str> = CONFIG_META.fields.iter().map(|f| f.name).collect();
+    assert_eq!(fields, ["name", "quiet"]);
+}
+
+#[cfg(feature = "extra")]
+#[test]
+fn test_make_item_const_cfg() {
+    assert_eq!(
+        squash_whitespace(CONFIG_SRC),
+        "#[doc = \" Settings whose fields depend on the `extra` feature.\"] #[derive(Debug)] pub \
+         struct Config { pub name : String, pub verbose : bool }"
+    );
+    assert!(squash_whitespace(EXTRA_ONLY_SRC).ends_with("pub struct ExtraOnly { pub level : u8, }"));
+    let fields: Vec<&str> = CONFIG_META.fields.iter().map(|f| f.name).collect();
+    assert_eq!(fields, ["name", "verbose"]);
+}
+
+#[test]
+fn test_inline_foreign_fn() {
+    use foreign_crate::ErrorKind;
+    assert_eq!(classify_status(204), None);
+    assert_eq!(classify_status(404), Some(ErrorKind::NotFound));
+    assert_eq!(
+        classify_status(403),
+        Some(ErrorKind::PermissionDenied {
+            path: String::new()
+        })
+    );
+    assert_eq!(classify_status(500), Some(ErrorKind::Other(500)));
+    assert_eq!(count_digits(7), 1);
+    assert_eq!(count_digits(12345), 5);
+}
@@ -0,0 +1,8 @@
+//! Locks down the errors developers actually run into. The expected output of each case lives
+//! next to it in `tests/ui`; run with `TRYBUILD=overwrite` to update it after an intended change.
+
+#[test]
+fn compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
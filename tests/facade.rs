@@ -0,0 +1,9 @@
+//! Checks that code using our macros only through `facade_crate` compiles. The cases in
+//! `tests/pass` can't name `macro_magic` or `types_crate` directly, since `user_crate` doesn't
+//! depend on either of them.
+
+#[test]
+fn facade() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/pass/*.rs");
+}
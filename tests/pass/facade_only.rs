@@ -0,0 +1,30 @@
+// Only `facade_crate` and `foreign_crate` can be named here, so this compiles as long as the
+// expansions refer to `macro_magic` and `types_crate` through the facade
+#[facade_crate::use_attr]
+use facade_crate::extend_enum;
+#[facade_crate::use_proc]
+use facade_crate::item_meta;
+#[facade_crate::use_proc]
+use facade_crate::make_item_const;
+
+make_item_const!(foreign_crate::StructTwo);
+item_meta!(foreign_crate::StructOne);
+
+#[extend_enum(foreign_crate::ErrorKind, from)]
+#[derive(Debug, PartialEq)]
+enum LocalErrorKind {
+    Timeout,
+}
+
+fn main() {
+    assert!(STRUCT_TWO_SRC.contains("field1"));
+    assert_eq!(
+        STRUCT_ONE_META.kind,
+        facade_crate::types_crate::ItemKind::Struct
+    );
+    assert_eq!(
+        LocalErrorKind::from(foreign_crate::ErrorKind::NotFound),
+        LocalErrorKind::NotFound
+    );
+    assert_ne!(LocalErrorKind::Timeout, LocalErrorKind::NotFound);
+}
@@ -0,0 +1,15 @@
+mod first_mod {
+    #[facade_crate::export_tokens]
+    struct MyStruct {
+        field1: usize,
+    }
+}
+
+mod second_mod {
+    #[facade_crate::export_tokens]
+    struct MyStruct {
+        field1: bool,
+    }
+}
+
+fn main() {}
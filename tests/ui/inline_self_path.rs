@@ -0,0 +1,7 @@
+#[facade_crate::use_proc]
+use facade_crate::inline_foreign_fn;
+
+// `is_error` calls `self::classify`, and the module `self` refers to isn't known here
+inline_foreign_fn!(foreign_crate::is_error as is_error);
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[facade_crate::use_proc]
+use facade_crate::assert_items_equivalent;
+
+assert_items_equivalent!(foreign_crate::StructOne, foreign_crate::StructTwo);
+
+fn main() {}
@@ -0,0 +1,5 @@
+use facade_crate::make_item_const;
+
+make_item_const!(foreign_crate::StructTwo);
+
+fn main() {}
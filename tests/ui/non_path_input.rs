@@ -0,0 +1,6 @@
+#[facade_crate::use_proc]
+use facade_crate::make_item_const;
+
+make_item_const!(struct MyStruct { field1: bool });
+
+fn main() {}
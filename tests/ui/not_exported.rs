@@ -0,0 +1,7 @@
+#[facade_crate::use_proc]
+use facade_crate::make_item_const;
+
+// `HashMap` exists, but has no `#[export_tokens]`
+make_item_const!(std::collections::HashMap);
+
+fn main() {}
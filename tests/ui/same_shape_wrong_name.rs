@@ -0,0 +1,9 @@
+#[facade_crate::use_attr]
+use facade_crate::assert_same_shape;
+
+#[assert_same_shape(foreign_crate::StructTwo)]
+pub struct WireTwo {
+    pub field2: bool,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#[facade_crate::use_attr]
+use facade_crate::assert_same_shape;
+
+// `StructTwo` has a single field, so the order is checked against `PointTwo { x, y }`
+#[assert_same_shape(foreign_crate::PointTwo)]
+pub struct WirePoint {
+    pub y: i32,
+    pub x: i32,
+}
+
+fn main() {}
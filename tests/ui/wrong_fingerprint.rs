@@ -0,0 +1,6 @@
+#[facade_crate::use_proc]
+use facade_crate::assert_item_fingerprint;
+
+assert_item_fingerprint!(foreign_crate::StructTwo, "0000000000000000");
+
+fn main() {}
@@ -0,0 +1,98 @@
+//! Types that are referenced by the code `macros_crate` generates. Proc macro crates can only
+//! export macros, so anything the expansions need at runtime has to live here.
+
+/// The kind of an item described by an [`ItemMeta`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Fn,
+    Const,
+    Static,
+    Type,
+    Mod,
+    Impl,
+    Use,
+    Other,
+}
+
+/// A typed description of an `#[export_tokens]` item, as emitted by `item_meta!`.
+///
+/// Types, generics, visibilities and attributes are stored as strings formatted the way
+/// `rustfmt` would write them, e.g. `Vec<u8>` rather than `Vec < u8 >`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ItemMeta {
+    pub kind: ItemKind,
+    /// The ident of the item as written in the foreign crate, not its export name. For impl
+    /// blocks this is the name of the implemented trait, if any.
+    pub ident: &'static str,
+    /// The generic parameters of the item including angle brackets, or `""` if it has none.
+    pub generics: &'static str,
+    /// The visibility of the item, or `""` if it is private.
+    pub visibility: &'static str,
+    /// The attributes attached to the item, excluding doc comments.
+    pub attributes: &'static [&'static str],
+    /// The fields of a struct or union, or the parameters of a fn.
+    pub fields: &'static [FieldMeta],
+    /// The variants of an enum.
+    pub variants: &'static [VariantMeta],
+    /// The return type of a fn, the type of a const, static or type alias, or the self type of
+    /// an impl block.
+    pub ty: Option<&'static str>,
+    /// The associated items of a trait or impl block, or the items of an inline module.
+    pub items: &'static [ItemMeta],
+}
+
+/// A single field of a struct, union or enum variant, or a single fn parameter.
+///
+/// Tuple fields are named after their index, i.e. `"0"`, `"1"`, etc.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldMeta {
+    pub name: &'static str,
+    pub ty: &'static str,
+}
+
+/// A single variant of an enum.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VariantMeta {
+    pub name: &'static str,
+    pub fields: &'static [FieldMeta],
+}
+
+/// The doc comments of an `#[export_tokens]` item, as emitted by `item_docs!`. Each doc is the
+/// text of its `///` lines joined with `\n`, or `""` if there is none.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ItemDocs {
+    /// The docs of the item itself.
+    pub item: &'static str,
+    /// The docs of every field of a struct or union, in order, documented or not.
+    pub fields: &'static [FieldDocs],
+    /// The docs of every variant of an enum, in order.
+    pub variants: &'static [VariantDocs],
+}
+
+/// The docs of a single field. Tuple fields are named after their index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldDocs {
+    pub name: &'static str,
+    pub docs: &'static str,
+}
+
+/// The docs of a single variant and its fields.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VariantDocs {
+    pub name: &'static str,
+    pub docs: &'static str,
+    pub fields: &'static [FieldDocs],
+}
+
+/// A call recorded by a `#[mock_of]` mock: the name of the method and its arguments formatted
+/// with `Debug`, leaving out the receiver. Arguments whose type depends on a generic parameter
+/// of the method are recorded as `"_"`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MockCall {
+    pub method: &'static str,
+    pub args: Vec<String>,
+}